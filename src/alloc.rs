@@ -0,0 +1,118 @@
+// Memory accounting. With the `alloc-stats` feature, all allocations go
+// through a counting wrapper around the system allocator.
+//
+// The counters are global, so the figures are only meaningful when a single
+// puzzle runs at a time.
+
+use serde::{Deserialize, Serialize};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+use std::sync::Mutex;
+
+pub const ENABLED: bool = cfg!(feature = "alloc-stats");
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemStats {
+    // Highest number of bytes live at once, above what was live before
+    pub peak_bytes: usize,
+    // Sum of all allocation sizes
+    pub total_bytes: usize,
+    pub allocations: usize,
+}
+
+pub struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// Only one measurement at a time
+static MEASURING: Mutex<()> = Mutex::new(());
+
+fn grow(size: usize) {
+    let current = CURRENT.fetch_add(size, Relaxed) + size;
+    PEAK.fetch_max(current, Relaxed);
+    TOTAL.fetch_add(size, Relaxed);
+    COUNT.fetch_add(1, Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Relaxed);
+    }
+
+    // A reallocation counts as freeing the old block and allocating the new
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            CURRENT.fetch_sub(layout.size(), Relaxed);
+            grow(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// Runs `f` and returns the allocations made while it ran, if the counting
+// allocator is enabled
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<MemStats>) {
+    if !ENABLED {
+        return (f(), None);
+    }
+
+    let _guard = MEASURING.lock().unwrap_or_else(|err| err.into_inner());
+    let base = CURRENT.load(Relaxed);
+    PEAK.store(base, Relaxed);
+    TOTAL.store(0, Relaxed);
+    COUNT.store(0, Relaxed);
+
+    let result = f();
+
+    let stats = MemStats {
+        peak_bytes: PEAK.load(Relaxed).saturating_sub(base),
+        total_bytes: TOTAL.load(Relaxed),
+        allocations: COUNT.load(Relaxed),
+    };
+    (result, Some(stats))
+}
+
+#[cfg(all(test, feature = "alloc-stats"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting() {
+        let (_, stats) = measure(|| {
+            let a = vec![0u8; 1000];
+            let b = vec![0u8; 2000];
+            drop(a);
+            drop(b);
+            let _c = vec![0u8; 500];
+        });
+        let stats = stats.unwrap();
+        assert!(stats.peak_bytes >= 3000);
+        assert!(stats.total_bytes >= 3500);
+        assert!(stats.allocations >= 3);
+    }
+}
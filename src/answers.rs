@@ -0,0 +1,197 @@
+use crate::registry::DEFAULT_YEAR;
+use crate::solution::{Answers, Parts};
+use fnv::FnvHasher;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::Hasher;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Check {
+    Pass,
+    Fail,
+    #[default]
+    Unknown,
+    // The part was not run
+    Skipped,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Check::Pass => write!(f, "PASS"),
+            Check::Fail => write!(f, "FAIL"),
+            Check::Unknown => write!(f, "UNKNOWN"),
+            Check::Skipped => write!(f, "SKIPPED"),
+        }
+    }
+}
+
+// Known answers for one input. Either part may be missing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Known {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+}
+
+// Database of known answers, keyed by day ("day01", or "2023-day01" outside
+// the default year) and a hash of the input,
+// so that answers for several different inputs can be kept side by side.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AnswerDb {
+    days: BTreeMap<String, BTreeMap<String, Known>>,
+}
+
+pub fn input_hash(input: &[u8]) -> String {
+    let mut hasher = FnvHasher::default();
+    hasher.write(input);
+    format!("{:016x}", hasher.finish())
+}
+
+pub fn day_key(year: u16, day: u8) -> String {
+    if year == DEFAULT_YEAR {
+        format!("day{:02}", day)
+    } else {
+        format!("{}-day{:02}", year, day)
+    }
+}
+
+fn check(known: &Option<String>, actual: &str) -> Check {
+    match known {
+        None => Check::Unknown,
+        Some(expected) if expected == actual => Check::Pass,
+        Some(_) => Check::Fail,
+    }
+}
+
+impl AnswerDb {
+    // A missing file is the same as an empty database
+    pub fn load(path: &Path) -> Result<AnswerDb, String> {
+        if !path.exists() {
+            return Ok(AnswerDb::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        toml::from_str(&text).map_err(|err| format!("Bad answers file {}: {}", path.display(), err))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string(self).map_err(|err| err.to_string())?;
+        std::fs::write(path, text)
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+    }
+
+    pub fn get(&self, year: u16, day: u8, input: &[u8]) -> Option<&Known> {
+        self.days.get(&day_key(year, day))?.get(&input_hash(input))
+    }
+
+    pub fn check(
+        &self,
+        year: u16,
+        day: u8,
+        input: &[u8],
+        answer: &Answers,
+        parts: Parts,
+    ) -> (Check, Check) {
+        let (part1, part2) = match self.get(year, day, input) {
+            None => (Check::Unknown, Check::Unknown),
+            Some(known) => (
+                check(&known.part1, &answer.part1),
+                check(&known.part2, &answer.part2),
+            ),
+        };
+        let skip = |run: bool, c: Check| if run { c } else { Check::Skipped };
+        (skip(parts.part1(), part1), skip(parts.part2(), part2))
+    }
+
+    // Fills in answers for parts which are not already known. Existing
+    // answers are never overwritten. Returns true if anything was added.
+    pub fn record(
+        &mut self,
+        year: u16,
+        day: u8,
+        input: &[u8],
+        answer: &Answers,
+        parts: Parts,
+    ) -> bool {
+        let known = self
+            .days
+            .entry(day_key(year, day))
+            .or_default()
+            .entry(input_hash(input))
+            .or_default();
+        let mut changed = false;
+        if parts.part1() && known.part1.is_none() {
+            known.part1 = Some(answer.part1.clone());
+            changed = true;
+        }
+        if parts.part2() && known.part2.is_none() {
+            known.part2 = Some(answer.part2.clone());
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(part1: &str, part2: &str) -> Answers {
+        Answers {
+            part1: part1.to_string(),
+            part2: part2.to_string(),
+        }
+    }
+
+    #[test]
+    fn record_and_check() {
+        let mut db = AnswerDb::default();
+        let answer = answers("1", "2");
+        assert_eq!(
+            (Check::Unknown, Check::Unknown),
+            db.check(2022, 1, b"x", &answer, Parts::Both)
+        );
+        assert!(db.record(2022, 1, b"x", &answer, Parts::Both));
+        assert!(!db.record(2022, 1, b"x", &answers("3", "4"), Parts::Both));
+        assert_eq!(
+            (Check::Pass, Check::Pass),
+            db.check(2022, 1, b"x", &answer, Parts::Both)
+        );
+        assert_eq!(
+            (Check::Fail, Check::Pass),
+            db.check(2022, 1, b"x", &answers("3", "2"), Parts::Both)
+        );
+        assert_eq!(
+            (Check::Unknown, Check::Unknown),
+            db.check(2022, 1, b"y", &answer, Parts::Both)
+        );
+        assert_eq!(
+            (Check::Skipped, Check::Pass),
+            db.check(2022, 1, b"x", &answers("", "2"), Parts::Part2)
+        );
+        assert!(db.record(2022, 2, b"x", &answers("", "2"), Parts::Part2));
+        assert_eq!(None, db.get(2022, 2, b"x").unwrap().part1);
+
+        // Other years are kept apart
+        assert_eq!(
+            (Check::Unknown, Check::Unknown),
+            db.check(2023, 1, b"x", &answer, Parts::Both)
+        );
+        assert!(db.record(2023, 1, b"x", &answers("5", "6"), Parts::Both));
+
+        let text = toml::to_string(&db).unwrap();
+        assert!(text.contains("[day01.") && text.contains("[2023-day01."));
+        let db: AnswerDb = toml::from_str(&text).unwrap();
+        assert_eq!(
+            (Check::Pass, Check::Pass),
+            db.check(2022, 1, b"x", &answer, Parts::Both)
+        );
+        assert_eq!(Some("5"), db.get(2023, 1, b"x").unwrap().part1.as_deref());
+    }
+}
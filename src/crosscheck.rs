@@ -0,0 +1,319 @@
+// Differential testing against reference solutions, such as the original
+// Erlang ones. A reference is an external command which reads an input and
+// prints the answers to part 1 and 2 on its first two lines of output. A
+// leading "Part N:" is ignored, so `aoc2022rust solve` from another checkout
+// works as a reference too.
+//
+// References are configured per day in a TOML file, with a default for days
+// without one of their own:
+//
+//   default = { command = ["../erlang/run.sh", "{day}"] }
+//   day22 = { command = ["escript", "day22.erl", "{input}"] }
+//
+// {year} and {day} are replaced by the year and day. The input is given on
+// stdin, and also as a file named by {input}.
+
+use crate::answers;
+use crate::generate;
+use crate::registry::{self, Puzzle};
+use crate::runner::Inputs;
+use crate::select;
+use crate::solution::{Answers, Parts};
+use crate::watchdog;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Reference {
+    pub command: Vec<String>,
+}
+
+// References keyed like the answers ("day22"), plus "default"
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Config {
+    references: BTreeMap<String, Reference>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        let config: Config = toml::from_str(&text)
+            .map_err(|err| format!("Bad crosscheck file {}: {}", path.display(), err))?;
+        match config.references.iter().find(|(_, r)| r.command.is_empty()) {
+            Some((key, _)) => Err(format!("The command for {} is empty", key)),
+            None => Ok(config),
+        }
+    }
+
+    pub fn reference(&self, year: u16, day: u8) -> Option<&Reference> {
+        self.references
+            .get(&answers::day_key(year, day))
+            .or_else(|| self.references.get("default"))
+    }
+}
+
+// The answers in the output of a reference, in order
+pub fn parse_output(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let answer = ["Part 1:", "Part 2:"]
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix))
+                .unwrap_or(line);
+            answer.trim().to_string()
+        })
+        .take(2)
+        .collect()
+}
+
+// Runs the reference on `input`, which is kept in `dir` while it runs.
+// Gives up (and kills the reference) after `timeout`.
+pub fn run(
+    reference: &Reference,
+    year: u16,
+    day: u8,
+    input: &[u8],
+    dir: &Path,
+    timeout: Duration,
+) -> Result<Vec<String>, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+    let input_path = dir.join("input.txt");
+    let output_path = dir.join("output.txt");
+    std::fs::write(&input_path, input)
+        .map_err(|err| format!("Failed to write {}: {}", input_path.display(), err))?;
+
+    let args: Vec<String> = reference
+        .command
+        .iter()
+        .map(|arg| {
+            arg.replace("{year}", &year.to_string())
+                .replace("{day}", &day.to_string())
+                .replace("{input}", &input_path.to_string_lossy())
+        })
+        .collect();
+    // Going through files means the reference cannot block on a full pipe
+    let open = |path: &Path, create: bool| {
+        let file = if create {
+            File::create(path)
+        } else {
+            File::open(path)
+        };
+        file.map_err(|err| format!("Failed to open {}: {}", path.display(), err))
+    };
+    let mut child = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(open(&input_path, false)?)
+        .stdout(open(&output_path, true)?)
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Failed to run {}: {}", args[0], err))?;
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(|err| err.to_string())? {
+            Some(status) => break status,
+            None if start.elapsed() > timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("reference timed out after {:?}", timeout));
+            }
+            None => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    if !status.success() {
+        return Err(format!("reference failed ({})", status));
+    }
+    let output = std::fs::read(&output_path)
+        .map_err(|err| format!("Failed to read {}: {}", output_path.display(), err))?;
+    Ok(parse_output(&String::from_utf8_lossy(&output)))
+}
+
+// The parts where the reference does not agree with us. Parts which the
+// puzzle does not have (part 2 of day 25) are empty here, and whatever the
+// reference prints for them is ignored.
+pub fn compare(ours: &Answers, theirs: &[String], parts: Parts) -> Vec<String> {
+    [
+        (1, parts.part1(), &ours.part1),
+        (2, parts.part2(), &ours.part2),
+    ]
+    .into_iter()
+    .filter(|(_, run, ours)| *run && !ours.is_empty())
+    .filter_map(|(part, _, ours)| match theirs.get(part - 1) {
+        Some(theirs) if theirs == ours => None,
+        Some(theirs) => Some(format!(
+            "part {}: {} here, {} from the reference",
+            part, ours, theirs
+        )),
+        None => Some(format!(
+            "part {}: {} here, nothing from the reference",
+            part, ours
+        )),
+    })
+    .collect()
+}
+
+// Checks the selected puzzles against their references and prints how they
+// compare. Returns true if the references agree on everything. Generated
+// inputs are given by their seeds and size.
+pub fn run_crosscheck(
+    selectors: &[String],
+    config: &Path,
+    generated: Option<(Range<u64>, Option<usize>)>,
+    year: u16,
+    inputs: &Inputs,
+    timeout: Duration,
+) -> Result<bool, String> {
+    let config = Config::load(config)?;
+    let registry = registry::registry();
+    let puzzles: Vec<&Puzzle> = registry.values().collect();
+    let subset = select::select(selectors, &puzzles, None, year)?;
+    let dir = std::env::temp_dir().join(format!("aoc-crosscheck-{}", std::process::id()));
+    let (mut agreed, mut disagreed, mut failed) = (0, 0, 0);
+
+    for (p, parts) in subset {
+        let Some(reference) = config.reference(p.year, p.day) else {
+            println!("Day {}: no reference", p.name());
+            continue;
+        };
+        let inputs: Vec<(String, Vec<u8>)> = match &generated {
+            None => {
+                let input = inputs.load(p)?.unwrap_or_else(|| p.input.to_vec());
+                vec![("input".to_string(), input)]
+            }
+            Some(_) if p.year != generate::YEAR => vec![],
+            Some((seeds, size)) => seeds
+                .clone()
+                .filter_map(|seed| {
+                    let input = generate::generate(p.day, *size, seed)?;
+                    Some((format!("seed {}", seed), input.into_bytes()))
+                })
+                .collect(),
+        };
+        if inputs.is_empty() {
+            println!("Day {}: no generator", p.name());
+            continue;
+        }
+
+        for (label, input) in inputs {
+            let solve = p.solve;
+            let ours = watchdog::run_isolated(timeout, {
+                let input = input.clone();
+                move |_| solve(&input, parts)
+            });
+            let theirs = run(reference, p.year, p.day, &input, &dir, timeout);
+            let problems = match (ours, theirs) {
+                (Ok(Ok((answers, _))), Ok(theirs)) => compare(&answers, &theirs, parts),
+                (Ok(Err(err)), _) => vec![err.in_day(p.year, p.day).to_string()],
+                (Err(failure), _) => vec![failure.to_string()],
+                (_, Err(err)) => {
+                    failed += 1;
+                    println!("Day {} ({}): {}", p.name(), label, err);
+                    continue;
+                }
+            };
+            if problems.is_empty() {
+                agreed += 1;
+                println!("Day {} ({}): OK", p.name(), label);
+            } else {
+                disagreed += 1;
+                println!("Day {} ({}): MISMATCH", p.name(), label);
+                for problem in problems {
+                    println!("    {}", problem);
+                }
+            }
+        }
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+
+    println!(
+        "Crosscheck: {} agreed, {} mismatched, {} reference failures",
+        agreed, disagreed, failed
+    );
+    Ok(disagreed == 0 && failed == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Reference {
+        Reference {
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+        }
+    }
+
+    #[test]
+    fn output() {
+        assert_eq!(vec!["1", "abc"], parse_output("1\n\n  abc \nextra\n"));
+        assert_eq!(
+            vec!["24000", "45000"],
+            parse_output("Part 1: 24000\nPart 2: 45000\n")
+        );
+        assert_eq!(Vec::<String>::new(), parse_output(""));
+
+        let ours = Answers {
+            part1: "1".to_string(),
+            part2: "2".to_string(),
+        };
+        let theirs = vec!["1".to_string(), "3".to_string()];
+        assert!(compare(&ours, &theirs, Parts::Part1).is_empty());
+        assert_eq!(
+            vec!["part 2: 2 here, 3 from the reference"],
+            compare(&ours, &theirs, Parts::Both)
+        );
+        assert_eq!(1, compare(&ours, &theirs[..1], Parts::Both).len());
+
+        let last_day = Answers {
+            part1: "2=-1=0".to_string(),
+            part2: String::new(),
+        };
+        let theirs = vec!["2=-1=0".to_string(), "Merry Christmas".to_string()];
+        assert!(compare(&last_day, &theirs, Parts::Both).is_empty());
+        assert!(compare(&last_day, &theirs[..1], Parts::Both).is_empty());
+    }
+
+    #[test]
+    fn config() {
+        let config: Config =
+            toml::from_str("default = { command = [\"a\"] }\nday22 = { command = [\"b\"] }\n")
+                .unwrap();
+        assert_eq!(vec!["b"], config.reference(2022, 22).unwrap().command);
+        assert_eq!(vec!["a"], config.reference(2022, 21).unwrap().command);
+        assert_eq!(vec!["a"], config.reference(2023, 22).unwrap().command);
+        assert_eq!(None, Config::default().reference(2022, 1));
+    }
+
+    #[test]
+    fn references() {
+        let dir = std::env::temp_dir().join(format!("aoc-crosscheck-{}", std::process::id()));
+        let timeout = Duration::from_secs(5);
+        let run =
+            |reference: &Reference, timeout| run(reference, 2022, 7, b"x\ny\n", &dir, timeout);
+
+        assert_eq!(
+            Ok(vec!["y".to_string(), "x".to_string()]),
+            run(&sh("sort -r"), timeout)
+        );
+        assert_eq!(
+            Ok(vec!["7".to_string(), "2".to_string()]),
+            run(&sh("echo {day}; wc -l < {input}"), timeout)
+        );
+        assert!(run(&sh("exit 3"), timeout).unwrap_err().contains("failed"));
+        assert!(run(&sh("sleep 5"), Duration::from_millis(100))
+            .unwrap_err()
+            .contains("timed out"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,18 +1,16 @@
-pub fn solve() -> (i32, i32) {
-    let bytes = include_bytes!("../inputs/input01.txt");
-    let s = String::from_utf8_lossy(bytes);
+use crate::error::{self, ParseResult};
+use crate::solution::Solution;
 
+pub struct Day01;
+
+// Returns the three largest calorie sums, largest first
+fn top_three(sums: &Vec<i32>) -> (i32, i32, i32) {
     let mut a: i32 = 0;
     let mut b: i32 = 0;
     let mut c: i32 = 0;
 
-    for group in s.split("\n\n") {
-        let sum: i32 = group
-            .trim()
-            .split("\n")
-            .map(|s| s.parse::<i32>().unwrap())
-            .sum();
-
+    for sum in sums {
+        let sum = *sum;
         if sum > a {
             c = b;
             b = a;
@@ -25,5 +23,34 @@ pub fn solve() -> (i32, i32) {
         }
     }
 
-    return (a, a + b + c);
+    (a, b, c)
+}
+
+impl Solution for Day01 {
+    type Input = Vec<i32>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(buf: &[u8]) -> ParseResult<Vec<i32>> {
+        let input = error::as_str(buf)?;
+        input
+            .split("\n\n")
+            .map(|group| {
+                group
+                    .trim()
+                    .split("\n")
+                    .map(|s| error::number::<i32>(input, s))
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn part1(sums: &Vec<i32>) -> i32 {
+        top_three(sums).0
+    }
+
+    fn part2(sums: &Vec<i32>) -> i32 {
+        let (a, b, c) = top_three(sums);
+        a + b + c
+    }
 }
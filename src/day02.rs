@@ -1,3 +1,6 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
+
 const ROCK: u8 = 65; // A
 const PAPER: u8 = 66; // B
 const SCISSORS: u8 = 67; // C
@@ -14,60 +17,60 @@ const S_ROCK: i32 = 1;
 const S_PAPER: i32 = 2;
 const S_SCISSORS: i32 = 3;
 
-pub fn solve() -> (i32, i32) {
-    let buf = include_bytes!("../inputs/input02.txt");
-    let mut i: usize = 0;
-    let mut p1: i32 = 0;
-    let mut p2: i32 = 0;
+pub struct Day02;
 
-    loop {
-        if i >= buf.len() {
-            break;
-        }
-        let left = buf[i];
-        let right = buf[i + 2];
-        i += 4;
+// Scores for a single round, as (part 1, part 2)
+fn score(left: u8, right: u8) -> (i32, i32) {
+    match (left, right) {
+        (ROCK, ROCK_OR_LOSE) => (S_ROCK + DRAW, S_SCISSORS + LOSE),
+        (ROCK, PAPER_OR_DRAW) => (S_PAPER + WIN, S_ROCK + DRAW),
+        (ROCK, SCISSORS_OR_WIN) => (S_SCISSORS + LOSE, S_PAPER + WIN),
+        (PAPER, ROCK_OR_LOSE) => (S_ROCK + LOSE, S_ROCK + LOSE),
+        (PAPER, PAPER_OR_DRAW) => (S_PAPER + DRAW, S_PAPER + DRAW),
+        (PAPER, SCISSORS_OR_WIN) => (S_SCISSORS + WIN, S_SCISSORS + WIN),
+        (SCISSORS, ROCK_OR_LOSE) => (S_ROCK + WIN, S_PAPER + LOSE),
+        (SCISSORS, PAPER_OR_DRAW) => (S_PAPER + LOSE, S_SCISSORS + DRAW),
+        (SCISSORS, SCISSORS_OR_WIN) => (S_SCISSORS + DRAW, S_ROCK + WIN),
+        _ => panic!(),
+    }
+}
+
+fn parse_lines(buf: &[u8]) -> ParseResult<Vec<(u8, u8)>> {
+    let input = error::as_str(buf)?;
+    input
+        .lines()
+        .map(|line| match line.as_bytes() {
+            [l @ ROCK..=SCISSORS, b' ', r @ ROCK_OR_LOSE..=SCISSORS_OR_WIN] => Ok((*l, *r)),
+            _ => Err(ParseError::at(input, line, "expected a round like \"A X\"")),
+        })
+        .collect()
+}
 
-        match (left, right) {
-            (ROCK, ROCK_OR_LOSE) => {
-                p1 += S_ROCK + DRAW;
-                p2 += S_SCISSORS + LOSE;
-            }
-            (ROCK, PAPER_OR_DRAW) => {
-                p1 += S_PAPER + WIN;
-                p2 += S_ROCK + DRAW;
-            }
-            (ROCK, SCISSORS_OR_WIN) => {
-                p1 += S_SCISSORS + LOSE;
-                p2 += S_PAPER + WIN;
-            }
-            (PAPER, ROCK_OR_LOSE) => {
-                p1 += S_ROCK + LOSE;
-                p2 += S_ROCK + LOSE;
-            }
-            (PAPER, PAPER_OR_DRAW) => {
-                p1 += S_PAPER + DRAW;
-                p2 += S_PAPER + DRAW;
-            }
-            (PAPER, SCISSORS_OR_WIN) => {
-                p1 += S_SCISSORS + WIN;
-                p2 += S_SCISSORS + WIN;
-            }
-            (SCISSORS, ROCK_OR_LOSE) => {
-                p1 += S_ROCK + WIN;
-                p2 += S_PAPER + LOSE;
-            }
-            (SCISSORS, PAPER_OR_DRAW) => {
-                p1 += S_PAPER + LOSE;
-                p2 += S_SCISSORS + DRAW;
-            }
-            (SCISSORS, SCISSORS_OR_WIN) => {
-                p1 += S_SCISSORS + DRAW;
-                p2 += S_ROCK + WIN;
-            }
-            _ => panic!(),
-        }
+impl Solution for Day02 {
+    type Input = Vec<(u8, u8)>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    // Rounds are read as bytes, four at a time, as going through a str is
+    // several times slower than solving. Anything else (such as CRLF line
+    // endings or an error) is left to `parse_lines`.
+    fn parse(buf: &[u8]) -> ParseResult<Vec<(u8, u8)>> {
+        buf.chunks(4)
+            .map(|round| match round {
+                [l @ ROCK..=SCISSORS, b' ', r @ ROCK_OR_LOSE..=SCISSORS_OR_WIN, b'\n'] => {
+                    Some((*l, *r))
+                }
+                _ => None,
+            })
+            .collect::<Option<_>>()
+            .map_or_else(|| parse_lines(buf), Ok)
+    }
+
+    fn part1(rounds: &Vec<(u8, u8)>) -> i32 {
+        rounds.iter().map(|(l, r)| score(*l, *r).0).sum()
     }
 
-    return (p1, p2);
+    fn part2(rounds: &Vec<(u8, u8)>) -> i32 {
+        rounds.iter().map(|(l, r)| score(*l, *r).1).sum()
+    }
 }
@@ -1,21 +1,61 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 use itertools::Itertools;
 
 type Mask = u64;
 type Prio = i32;
 
-pub fn solve() -> (u32, u32) {
-    let buf = include_bytes!("../inputs/input03.txt");
-    let s = String::from_utf8_lossy(buf);
+pub struct Day03;
 
-    let mut p1: Prio = 0;
-    let mut p2: Prio = 0;
+impl Solution for Day03 {
+    type Input = Vec<String>;
+    type Part1 = u32;
+    type Part2 = u32;
 
-    for (a, b, c) in s.split("\n").tuples() {
-        p1 += count1(a) + count1(b) + count1(c);
-        p2 += count2(a, b, c);
+    fn parse(buf: &[u8]) -> ParseResult<Vec<String>> {
+        let input = error::as_str(buf)?;
+        let lines: Vec<&str> = input.lines().collect();
+        // The elves come in groups of three
+        if !lines.len().is_multiple_of(3) {
+            let line = lines[lines.len() / 3 * 3];
+            return Err(ParseError::at(
+                input,
+                line,
+                "expected a group of three rucksacks",
+            ));
+        }
+        lines
+            .into_iter()
+            .map(|line| {
+                error::check_chars(input, line, |c| c.is_ascii_alphabetic())?;
+                // The compartments hold the same number of items
+                if !line.len().is_multiple_of(2) {
+                    return Err(ParseError::at(
+                        input,
+                        line,
+                        "expected an even number of items",
+                    ));
+                }
+                Ok(line.to_string())
+            })
+            .collect()
     }
 
-    return (p1 as u32, p2 as u32);
+    fn part1(rucksacks: &Vec<String>) -> u32 {
+        let mut p1: Prio = 0;
+        for (a, b, c) in rucksacks.iter().tuples() {
+            p1 += count1(a) + count1(b) + count1(c);
+        }
+        p1 as u32
+    }
+
+    fn part2(rucksacks: &Vec<String>) -> u32 {
+        let mut p2: Prio = 0;
+        for (a, b, c) in rucksacks.iter().tuples() {
+            p2 += count2(a, b, c);
+        }
+        p2 as u32
+    }
 }
 
 fn count1(s: &str) -> Prio {
@@ -1,37 +1,44 @@
+use crate::error::{self, ParseResult};
+use crate::solution::Solution;
 use std::ops::RangeInclusive;
 
-fn to_int(opt: Option<&str>) -> i32 {
-    opt.unwrap().parse::<i32>().unwrap()
-}
+pub struct Day04;
 
-pub fn solve() -> (u32, u32) {
-    let buf = include_bytes!("../inputs/input04.txt");
-    let s = String::from_utf8_lossy(buf);
-    let mut p1: u32 = 0;
-    let mut p2: u32 = 0;
-
-    for line in s.trim().split("\n") {
-        let mut items = line.split([',', '-']);
-        let a = to_int(items.next());
-        let b = to_int(items.next());
-        let c = to_int(items.next());
-        let d = to_int(items.next());
-        let a_range = a..=b;
-        let b_range = c..=d;
-        if contains_either(&a_range, &b_range) {
-            p1 += 1;
-        }
-        if overlaps(&a_range, &b_range) {
-            p2 += 1;
+type Pair = (RangeInclusive<i32>, RangeInclusive<i32>);
+
+impl Solution for Day04 {
+    type Input = Vec<Pair>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(buf: &[u8]) -> ParseResult<Vec<Pair>> {
+        let s = error::as_str(buf)?;
+        let mut pairs = Vec::new();
+
+        for line in s.trim().split("\n") {
+            let mut items = line.split([',', '-']);
+            let a = error::next_number(s, line, items.next())?;
+            let b = error::next_number(s, line, items.next())?;
+            let c = error::next_number(s, line, items.next())?;
+            let d = error::next_number(s, line, items.next())?;
+            pairs.push((a..=b, c..=d));
         }
+        Ok(pairs)
+    }
+
+    fn part1(pairs: &Vec<Pair>) -> u32 {
+        pairs.iter().filter(|(a, b)| contains_either(a, b)).count() as u32
+    }
+
+    fn part2(pairs: &Vec<Pair>) -> u32 {
+        pairs.iter().filter(|(a, b)| overlaps(a, b)).count() as u32
     }
-    (p1, p2)
 }
 
-fn contains_either(a: &RangeInclusive<i32>, b: &RangeInclusive<i32>) -> bool {
+pub fn contains_either(a: &RangeInclusive<i32>, b: &RangeInclusive<i32>) -> bool {
     (a.contains(b.start()) && a.contains(b.end())) || (b.contains(a.start()) && b.contains(a.end()))
 }
 
-fn overlaps(a: &RangeInclusive<i32>, b: &RangeInclusive<i32>) -> bool {
+pub fn overlaps(a: &RangeInclusive<i32>, b: &RangeInclusive<i32>) -> bool {
     (a.end() >= b.start() && a.end() <= b.end()) || (b.end() >= a.start()) && (b.end() <= a.end())
 }
@@ -1,62 +1,107 @@
-const NUM_STACKS: usize = 9;
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
+
 type Stack = Vec<char>;
 type Stacks = Vec<Stack>;
+type Move = (usize, usize, usize);
 
-pub fn solve() -> (String, String) {
-    let buf = include_bytes!("../inputs/input05.txt");
-    let s = String::from_utf8_lossy(buf);
-    let mut stacks1: Stacks = Vec::new();
-    let mut stacks2: Stacks = Vec::new();
+pub struct Day05;
 
-    for _i in 0..NUM_STACKS {
-        stacks1.push(Vec::new());
-        stacks2.push(Vec::new());
-    }
+pub struct Input {
+    stacks: Stacks,
+    moves: Vec<Move>,
+}
+
+impl Solution for Day05 {
+    type Input = Input;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(buf: &[u8]) -> ParseResult<Input> {
+        let s = error::as_str(buf)?;
+        let mut moves: Vec<Move> = Vec::new();
+
+        let (stack_part, command_part) = s
+            .split_once("\n\n")
+            .ok_or_else(|| ParseError::eof(s, "expected an empty line after the stacks"))?;
+
+        // The last line holds the stack numbers, and tells us how many
+        // stacks there are
+        let mut lines: Vec<&str> = stack_part.split("\n").collect();
+        let labels = lines.pop().unwrap();
+        let num_stacks = labels.split_whitespace().count();
+        let mut stacks: Stacks = vec![Vec::new(); num_stacks];
 
-    let mut parts = s.split("\n\n");
-    let stack_part = parts.next().unwrap();
-    let command_part = parts.next().unwrap();
+        for line in lines {
+            parse_crate_line(line.as_bytes(), &mut stacks);
+        }
+
+        let stack_idx = |text: &str| match error::number::<usize>(s, text)? {
+            n @ 1.. if n <= num_stacks => Ok(n - 1),
+            _ => Err(ParseError::at(s, text, "no such stack")),
+        };
+
+        // The moves can only take crates which are there
+        let mut heights: Vec<usize> = stacks.iter().map(|stack| stack.len()).collect();
+        for line in command_part.trim().split("\n") {
+            let words: Vec<&str> = line.split(" ").collect();
+            match words[..] {
+                ["move", n, "from", from, "to", to] => {
+                    let (count, from, to) =
+                        (error::number(s, n)?, stack_idx(from)?, stack_idx(to)?);
+                    if count > heights[from] {
+                        return Err(ParseError::at(s, n, "not that many crates on the stack"));
+                    }
+                    heights[from] -= count;
+                    heights[to] += count;
+                    moves.push((count, from, to));
+                }
+                _ => {
+                    return Err(ParseError::at(
+                        s,
+                        line,
+                        "expected a move like \"move 1 from 2 to 3\"",
+                    ))
+                }
+            }
+        }
 
-    for line in stack_part.split("\n") {
-        let bytes = line.as_bytes();
-        parse_crate_line(bytes, &mut stacks1);
-        parse_crate_line(bytes, &mut stacks2);
+        Ok(Input { stacks, moves })
     }
 
-    for line in command_part.trim().split("\n") {
-        let mut words = line.split(" ");
-        words.next();
-        let n = words.next().unwrap().parse::<usize>().unwrap();
-        words.next();
-        let from_idx = words.next().unwrap().parse::<usize>().unwrap() - 1;
-        words.next();
-        let to_idx = words.next().unwrap().parse::<usize>().unwrap() - 1;
-
-        let mut to_move1: Stack = stacks1[from_idx].drain(0..n).collect();
-        to_move1.reverse();
-        stacks1[to_idx].splice(0..0, to_move1);
-
-        let to_move2: Stack = stacks2[from_idx].drain(0..n).collect();
-        stacks2[to_idx].splice(0..0, to_move2);
+    fn part1(input: &Input) -> String {
+        let mut stacks = input.stacks.clone();
+        for (n, from_idx, to_idx) in &input.moves {
+            let mut to_move: Stack = stacks[*from_idx].drain(0..*n).collect();
+            to_move.reverse();
+            stacks[*to_idx].splice(0..0, to_move);
+        }
+        top_crates(&stacks)
     }
 
-    (top_crates(&stacks1), top_crates(&stacks2))
+    fn part2(input: &Input) -> String {
+        let mut stacks = input.stacks.clone();
+        for (n, from_idx, to_idx) in &input.moves {
+            let to_move: Stack = stacks[*from_idx].drain(0..*n).collect();
+            stacks[*to_idx].splice(0..0, to_move);
+        }
+        top_crates(&stacks)
+    }
 }
 
 fn top_crates(stacks: &Stacks) -> String {
     stacks
         .iter()
-        .map(|stack| stack.first().unwrap())
+        .filter_map(|stack| stack.first())
         .collect::<String>()
 }
 
 fn parse_crate_line(line: &[u8], stacks: &mut Stacks) {
-    for i in 0..NUM_STACKS {
-        let c: u8 = line[i * 4 + 1];
-        if c == 32 {
-            continue;
-        } else {
-            stacks[i].push(c as char);
+    for (i, stack) in stacks.iter_mut().enumerate() {
+        // Trailing empty stacks may be missing from the line
+        match line.get(i * 4 + 1) {
+            None | Some(b' ') => continue,
+            Some(c) => stack.push(*c as char),
         }
     }
 }
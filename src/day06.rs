@@ -1,9 +1,44 @@
-pub fn solve() -> (i32, i32) {
-    let buf = include_bytes!("../inputs/input06.txt");
-    let mut p1: i32 = 0;
-    let mut p2: i32 = 0;
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
+
+pub struct Day06;
+
+// The input is only needed to find the two markers, so that is done here
+impl Solution for Day06 {
+    type Input = (usize, usize);
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(buf: &[u8]) -> ParseResult<(usize, usize)> {
+        let input = error::as_str(buf)?;
+        let line = input.trim_end();
+        error::check_chars(input, line, |c| c.is_ascii_lowercase())?;
+        // Room for a message marker, with something after it
+        if line.len() <= 14 {
+            return Err(ParseError::eof(input, "input is too short"));
+        }
+        let buf = line.as_bytes();
+        let packet = start_of_packet(buf)
+            .ok_or_else(|| ParseError::eof(input, "no start-of-packet marker"))?;
+        let message = start_of_message(buf, packet)
+            .ok_or_else(|| ParseError::eof(input, "no start-of-message marker"))?;
+        Ok((packet, message))
+    }
 
-    for i in 0..buf.len() {
+    fn part1((packet, _): &(usize, usize)) -> i32 {
+        *packet as i32 + 4
+    }
+
+    fn part2((_, message): &(usize, usize)) -> i32 {
+        *message as i32 + 14
+    }
+}
+
+// Index of the first of four different characters. Inlined into `parse`,
+// the scans are about twice as slow.
+#[inline(never)]
+fn start_of_packet(buf: &[u8]) -> Option<usize> {
+    for i in 0..buf.len().saturating_sub(3) {
         let a = buf[i];
         let b = buf[i + 1];
         let c = buf[i + 2];
@@ -12,13 +47,19 @@ pub fn solve() -> (i32, i32) {
         if a == b || a == c || a == d || b == c || b == d || c == d {
             continue;
         } else {
-            p1 = i as i32;
-            break;
+            return Some(i);
         }
     }
 
+    None
+}
+
+// Index of the first of fourteen different characters. A message marker
+// cannot start before the packet marker, so start searching from there.
+#[inline(never)]
+fn start_of_message(buf: &[u8], from: usize) -> Option<usize> {
     // well...
-    for i in p1 as usize..buf.len() - 14 {
+    for i in from..buf.len().saturating_sub(13) {
         let a = buf[i];
         let b = buf[i + 1];
         let c = buf[i + 2];
@@ -152,12 +193,9 @@ pub fn solve() -> (i32, i32) {
         {
             continue;
         } else {
-            p2 = i as i32;
-            break;
+            return Some(i);
         }
     }
 
-    p1 += 4;
-    p2 += 14;
-    (p1, p2)
+    None
 }
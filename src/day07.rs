@@ -1,66 +1,103 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 type SubDirMap = HashMap<PathBuf, Vec<PathBuf>>;
-type SizeMap = HashMap<PathBuf, i64>;
+pub type SizeMap = HashMap<PathBuf, i64>;
 
-pub fn solve() -> (i64, i64) {
-    let buf = include_bytes!("../inputs/input07.txt");
-    let s = String::from_utf8_lossy(buf);
-    let p1: i64;
-    let p2: i64;
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = SizeMap;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(buf: &[u8]) -> ParseResult<SizeMap> {
+        parse_sizes(buf)
+    }
+
+    fn part1(sizes: &SizeMap) -> i64 {
+        sizes.values().filter(|v| **v <= 100_000).sum()
+    }
+
+    // The root is always there, and deleting it is always enough
+    fn part2(sizes: &SizeMap) -> i64 {
+        const MAX_FILE_SIZE: i64 = 40_000_000;
+        let total_used = sizes[&PathBuf::new()];
+
+        *sizes
+            .values()
+            .filter(|v| total_used - **v <= MAX_FILE_SIZE)
+            .min()
+            .unwrap()
+    }
+}
+
+// Returns the total size of each directory, including subdirectories
+fn parse_sizes(buf: &[u8]) -> ParseResult<SizeMap> {
+    let s = error::as_str(buf)?;
     let mut cwd: PathBuf = PathBuf::new();
     let mut sub_dirs: SubDirMap = HashMap::new();
     let mut sizes: SizeMap = HashMap::new();
+    // Directories which have been listed, and which have been visited
+    let mut listed: Vec<(PathBuf, &str)> = vec![];
+    let mut entered: HashSet<PathBuf> = HashSet::new();
 
     for line in s.trim().split("\n") {
         let words = line.split(' ').collect::<Vec<&str>>();
         match words[..] {
             ["$", "cd", "/"] => {
                 cwd = PathBuf::new();
+                entered.insert(cwd.clone());
             }
             ["$", "cd", ".."] => {
                 cwd.pop();
             }
             ["$", "cd", dir] => {
                 cwd.push(dir);
+                entered.insert(cwd.clone());
             }
             ["$", "ls"] => {
                 // nop
             }
+            // Anything else could make a directory its own subdirectory
+            ["dir", dir] if dir == "." || dir == ".." || dir.contains('/') => {
+                return Err(ParseError::at(s, dir, "expected a directory name"));
+            }
             ["dir", dir] => {
                 let mut full_dir = cwd.clone();
                 full_dir.push(dir);
+                listed.push((full_dir.clone(), line));
                 sub_dirs.entry(cwd.clone()).or_insert(vec![]).push(full_dir);
             }
             [size, _file] => {
-                *sizes.entry(cwd.clone()).or_insert(0) += size.parse::<i64>().unwrap();
+                *sizes.entry(cwd.clone()).or_insert(0) += error::number::<i64>(s, size)?;
             }
-            _ => panic!(),
+            _ => return Err(ParseError::at(s, line, "expected a command or a listing")),
         }
     }
 
-    compute_sizes(PathBuf::new(), &sub_dirs, &mut sizes);
-
-    p1 = sizes.values().filter(|v| **v <= 100_000).sum();
-
-    const MAX_FILE_SIZE: i64 = 40_000_000;
-    let total_used = sizes.get(&PathBuf::new()).unwrap();
-
-    p2 = *sizes
-        .values()
-        .filter(|v| total_used - **v <= MAX_FILE_SIZE)
-        .min()
-        .unwrap();
+    // The size of a directory which was never listed is not known, which
+    // is what a truncated input looks like
+    if !entered.contains(&PathBuf::new()) {
+        let first = s.trim().lines().next().unwrap_or_default();
+        return Err(ParseError::at(s, first, "expected \"$ cd /\""));
+    }
+    if let Some((_, line)) = listed.iter().find(|(dir, _)| !entered.contains(dir)) {
+        return Err(ParseError::at(s, line, "directory is never entered"));
+    }
 
-    (p1, p2)
+    compute_sizes(Path::new(""), &sub_dirs, &mut sizes);
+    Ok(sizes)
 }
 
-fn compute_sizes(root: PathBuf, sub_dirs: &SubDirMap, sizes: &mut SizeMap) {
-    if let Some(list) = sub_dirs.get(&root) {
-        for d in list {
-            compute_sizes(d.clone(), sub_dirs, sizes);
-            *sizes.entry(root.clone()).or_insert(0) += *sizes.get(d).unwrap();
-        }
-    }
+// Adds the sizes of the subdirectories to that of `dir`, and returns it
+fn compute_sizes(dir: &Path, sub_dirs: &SubDirMap, sizes: &mut SizeMap) -> i64 {
+    let subdirs: i64 = sub_dirs.get(dir).map_or(0, |list| {
+        list.iter().map(|d| compute_sizes(d, sub_dirs, sizes)).sum()
+    });
+    let size = sizes.entry(dir.to_path_buf()).or_insert(0);
+    *size += subdirs;
+    *size
 }
@@ -1,35 +1,72 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 use std::cmp::max;
 
-const WIDTH: i32 = 99;
-const HEIGHT: i32 = 99;
-
-type Grid = Vec<u8>;
+// The raw input, rows separated by newlines
+pub struct Grid {
+    cells: Vec<u8>,
+    width: i32,
+    height: i32,
+}
 
 fn read_grid(grid: &Grid, x: i32, y: i32) -> char {
-    grid[(y * (WIDTH + 1) + x) as usize] as char
+    grid.cells[(y * (grid.width + 1) + x) as usize] as char
 }
 
-fn is_valid_coord(x: i32, y: i32) -> bool {
-    x >= 0 && x < WIDTH && y >= 0 && y < HEIGHT
+fn is_valid_coord(grid: &Grid, x: i32, y: i32) -> bool {
+    x >= 0 && x < grid.width && y >= 0 && y < grid.height
 }
 
-pub fn solve() -> (i64, i64) {
-    let buf = include_bytes!("../inputs/input08.txt");
-    let grid = buf.to_vec();
-    let mut p1: i64 = 0;
-    let mut p2: i64 = 0;
-
-    for x in 0..WIDTH {
-        for y in 0..HEIGHT {
-            if is_tree_visible(x, y, &grid) {
-                p1 += 1;
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Grid;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(buf: &[u8]) -> ParseResult<Grid> {
+        let input = error::as_str(buf)?;
+        let width = input
+            .find('\n')
+            .ok_or_else(|| ParseError::eof(input, "expected a newline"))?;
+        for line in input.lines() {
+            error::check_chars(input, line, |c| c.is_ascii_digit())?;
+            if line.len() != width {
+                return Err(ParseError::at(
+                    input,
+                    line,
+                    "rows must have the same length",
+                ));
             }
+        }
+        Ok(Grid {
+            cells: buf.to_vec(),
+            width: width as i32,
+            height: input.lines().count() as i32,
+        })
+    }
 
-            p2 = max(p2, scenic_score(x, y, &grid))
+    fn part1(grid: &Grid) -> i64 {
+        let mut p1: i64 = 0;
+        for x in 0..grid.width {
+            for y in 0..grid.height {
+                if is_tree_visible(x, y, grid) {
+                    p1 += 1;
+                }
+            }
         }
+        p1
     }
 
-    (p1, p2)
+    fn part2(grid: &Grid) -> i64 {
+        let mut p2: i64 = 0;
+        for x in 0..grid.width {
+            for y in 0..grid.height {
+                p2 = max(p2, scenic_score(x, y, grid))
+            }
+        }
+        p2
+    }
 }
 
 fn is_tree_visible(x: i32, y: i32, grid: &Grid) -> bool {
@@ -41,7 +78,7 @@ fn is_tree_visible(x: i32, y: i32, grid: &Grid) -> bool {
         let mut y0 = y + dy;
 
         loop {
-            if !is_valid_coord(x0, y0) {
+            if !is_valid_coord(grid, x0, y0) {
                 return true;
             } else {
                 if read_grid(grid, x0, y0) >= th {
@@ -68,7 +105,7 @@ fn scenic_score(x: i32, y: i32, grid: &Grid) -> i64 {
         let mut dist = 0;
 
         loop {
-            if !is_valid_coord(x0, y0) {
+            if !is_valid_coord(grid, x0, y0) {
                 score *= dist;
                 break;
             } else {
@@ -90,10 +127,12 @@ fn scenic_score(x: i32, y: i32, grid: &Grid) -> i64 {
 
 #[cfg(test)]
 mod tests {
+    use crate::solution::Solution;
+
     #[test]
     fn read_grid() {
         let buf = include_bytes!("../inputs/input08.txt");
-        let grid = buf.to_vec();
+        let grid = super::Day08::parse(buf).unwrap();
         assert_eq!('2', super::read_grid(&grid, 0, 0));
         assert_eq!('3', super::read_grid(&grid, 0, 1));
         assert_eq!('1', super::read_grid(&grid, 0, 98));
@@ -1,20 +1,96 @@
 // use itertools::Itertools;
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::{Parts, Solution};
+use crate::visualize::{Frame, Frames, Visualize};
 use std::collections::HashSet;
 
-pub fn solve() -> (i64, i64) {
-    let buf = include_bytes!("../inputs/input09.txt");
-    let mut instrs: Vec<(char, i32)> = vec![];
+pub struct Day09;
 
-    for line in String::from_utf8_lossy(buf).trim().split("\n") {
-        let mut words = line.split(' ');
-        let dir: char = words.next().unwrap().chars().next().unwrap();
-        let n = words.next().unwrap().parse::<i32>().unwrap();
-        instrs.push((dir, n));
+impl Solution for Day09 {
+    type Input = Vec<(char, i32)>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(buf: &[u8]) -> ParseResult<Vec<(char, i32)>> {
+        let input = error::as_str(buf)?;
+        let mut instrs: Vec<(char, i32)> = vec![];
+
+        for line in input.trim().split("\n") {
+            let mut words = line.split(' ');
+            let dir = match words.next() {
+                Some(dir @ ("U" | "D" | "L" | "R")) => dir.chars().next().unwrap(),
+                _ => return Err(ParseError::at(input, line, "expected U, D, L or R")),
+            };
+            let n = error::next_number(input, line, words.next())?;
+            instrs.push((dir, n));
+        }
+        Ok(instrs)
     }
 
-    let p1: i64 = simulate(&instrs, 2);
-    let p2: i64 = simulate(&instrs, 10);
-    (p1, p2)
+    fn part1(instrs: &Vec<(char, i32)>) -> i64 {
+        simulate(instrs, 2)
+    }
+
+    fn part2(instrs: &Vec<(char, i32)>) -> i64 {
+        simulate(instrs, 10)
+    }
+}
+
+// The rope after each instruction, with the positions visited by the tail
+// drawn as '#'. The rope has 2 knots in part 1 and 10 in part 2.
+impl Visualize for Day09 {
+    fn frames(instrs: Vec<(char, i32)>, parts: Parts) -> Result<Frames, String> {
+        let num_knots = if parts == Parts::Part2 { 10 } else { 2 };
+        let names: Vec<char> = if num_knots == 2 {
+            vec!['H', 'T']
+        } else {
+            "H123456789".chars().collect()
+        };
+
+        // The knots never leave the area covered by the head
+        let mut head = (0, 0);
+        let (mut min, mut max) = ((0, 0), (0, 0));
+        for (dir, n) in &instrs {
+            for _ in 0..*n {
+                move_head(&mut head, *dir);
+                min = (min.0.min(head.0), min.1.min(head.1));
+                max = (max.0.max(head.0), max.1.max(head.1));
+            }
+        }
+
+        let mut rope: Vec<(i32, i32)> = vec![(0, 0); num_knots];
+        let mut visited: HashSet<(i32, i32)> = HashSet::from([(0, 0)]);
+        Ok(Box::new((0..=instrs.len()).map(move |i| {
+            let caption = match i.checked_sub(1).map(|i| instrs[i]) {
+                Some((dir, n)) => {
+                    for _ in 0..n {
+                        move_head(&mut rope[0], dir);
+                        for k in 1..num_knots {
+                            rope[k] = move_follow(&rope[k - 1], &rope[k]);
+                        }
+                        visited.insert(*rope.last().unwrap());
+                    }
+                    format!("{} {}", dir, n)
+                }
+                None => "initial state".to_string(),
+            };
+            Frame::draw(
+                caption,
+                (max.0 - min.0 + 1) as usize,
+                (max.1 - min.1 + 1) as usize,
+                |col, row| {
+                    let pos = (min.0 + col as i32, min.1 + row as i32);
+                    match rope.iter().position(|knot| *knot == pos) {
+                        Some(k) => names[k],
+                        None if pos == (0, 0) => 's',
+                        None if visited.contains(&pos) => '#',
+                        None => '.',
+                    }
+                },
+            )
+            .with_focus((rope[0].0 - min.0) as usize, (rope[0].1 - min.1) as usize)
+        })))
+    }
 }
 
 fn simulate(instrs: &Vec<(char, i32)>, num_knots: i32) -> i64 {
@@ -51,7 +127,7 @@ fn move_head(head: &mut (i32, i32), dir: char) {
     }
 }
 
-fn move_follow(h: &(i32, i32), t: &(i32, i32)) -> (i32, i32) {
+pub fn move_follow(h: &(i32, i32), t: &(i32, i32)) -> (i32, i32) {
     if is_touching(h, t) {
         return *t;
     }
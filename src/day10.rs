@@ -1,71 +1,97 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::ocr;
+use crate::solution::Solution;
+
 type Crt = [char; 240]; // 40 * 6 chars
 
-pub fn solve() -> (i64, String) {
-    let buf = include_bytes!("../inputs/input10.txt");
+pub enum Instr {
+    Noop,
+    Addx(i64),
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<Instr>;
+    type Part1 = i64;
+    type Part2 = String;
 
-    let p1 = solve_p1(buf);
-    let p2 = solve_p2(buf);
+    fn parse(buf: &[u8]) -> ParseResult<Vec<Instr>> {
+        let input = error::as_str(buf)?;
+        input
+            .trim()
+            .lines()
+            .map(|line| match line.split(' ').collect::<Vec<&str>>()[..] {
+                ["noop"] => Ok(Instr::Noop),
+                ["addx", num] => Ok(Instr::Addx(error::number(input, num)?)),
+                _ => Err(ParseError::at(input, line, "expected noop or addx")),
+            })
+            .collect()
+    }
+
+    fn part1(instrs: &Vec<Instr>) -> i64 {
+        solve_p1(instrs)
+    }
 
-    (p1, p2)
+    // The letters on the screen. A screen which does not show letters
+    // (like the one in the example) fails the part.
+    fn part2(instrs: &Vec<Instr>) -> String {
+        ocr::read(&solve_p2(instrs)).unwrap_or_else(|err| panic!("cannot read the screen: {}", err))
+    }
 }
 
-fn solve_p1(buf: &[u8]) -> i64 {
+fn solve_p1(instrs: &Vec<Instr>) -> i64 {
     let mut sum: i64 = 0;
     let mut x: i64 = 1;
     let mut cycle: i64 = 1;
 
-    for line in String::from_utf8_lossy(buf).trim().lines() {
-        match line.split(' ').collect::<Vec<&str>>()[..] {
-            ["noop"] if is_interesting_cycle(cycle) => {
+    for instr in instrs {
+        match instr {
+            Instr::Noop if is_interesting_cycle(cycle) => {
                 sum += cycle * x;
                 cycle += 1;
             }
-            ["noop"] => {
+            Instr::Noop => {
                 cycle += 1;
             }
-            ["addx", num] if is_interesting_cycle(cycle) => {
-                let n = num.parse::<i64>().unwrap();
+            Instr::Addx(n) if is_interesting_cycle(cycle) => {
                 sum += cycle * x;
                 x += n;
                 cycle += 2;
             }
-            ["addx", num] if is_interesting_cycle(cycle + 1) => {
-                let n = num.parse::<i64>().unwrap();
+            Instr::Addx(n) if is_interesting_cycle(cycle + 1) => {
                 sum += (cycle + 1) * x;
                 x += n;
                 cycle += 2;
             }
-            ["addx", num] => {
-                let n = num.parse::<i64>().unwrap();
+            Instr::Addx(n) => {
                 x += n;
                 cycle += 2;
             }
-            _ => panic!(),
         };
     }
 
     sum
 }
 
-fn solve_p2(buf: &[u8]) -> String {
+// The screen, row by row
+pub fn solve_p2(instrs: &Vec<Instr>) -> String {
     let mut x: usize = 1;
     let mut pos: usize = 0;
     let mut crt: Crt = [' '; 240];
 
-    for line in String::from_utf8_lossy(buf).trim().lines() {
-        match line.split(' ').collect::<Vec<&str>>()[..] {
-            ["noop"] => {
+    for instr in instrs {
+        match instr {
+            Instr::Noop => {
                 draw(x, pos, &mut crt);
                 pos += 1;
             }
-            ["addx", num] => {
-                let n = num.parse::<i32>().unwrap();
+            Instr::Addx(n) => {
                 draw(x, pos, &mut crt);
                 draw(x, pos + 1, &mut crt);
                 pos += 2;
-                x = ((x as i32) + n) as usize;
+                x = ((x as i64) + n) as usize;
             }
-            _ => panic!(),
         };
     }
 
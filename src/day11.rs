@@ -1,10 +1,13 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
+
 enum Op {
     PLUS(i64),
     MULT(i64),
     SQUARED,
 }
 
-struct Monkey {
+pub struct Monkey {
     num: usize,
     items: Vec<i64>,
     op: Op,
@@ -13,13 +16,17 @@ struct Monkey {
     on_false: usize,
 }
 
-fn parse(buf: &[u8]) -> Vec<Monkey> {
+fn parse(buf: &[u8]) -> ParseResult<Vec<Monkey>> {
+    let s = error::as_str(buf)?;
     let mut monkeys: Vec<Monkey> = Vec::new();
     let mut current: usize = 0;
 
-    for line in String::from_utf8_lossy(buf).trim().split("\n") {
+    for line in s.trim().split("\n") {
         let words = line.trim().split([' ', ':', ',']);
         let words_vec = words.collect::<Vec<&str>>();
+        if monkeys.is_empty() && !matches!(words_vec[..], ["Monkey", ..]) {
+            return Err(ParseError::at(s, line, "expected \"Monkey N:\""));
+        }
         match words_vec[..] {
             ["Monkey", ..] => {
                 current = monkeys.len();
@@ -35,35 +42,43 @@ fn parse(buf: &[u8]) -> Vec<Monkey> {
             ["Starting", "items", ..] => {
                 let monkey = &mut monkeys[current];
 
-                for s in &words_vec[3..] {
-                    if let Ok(n) = s.parse::<i64>() {
-                        monkey.items.push(n);
-                    }
+                for item in words_vec[3..].iter().filter(|w| !w.is_empty()) {
+                    monkey.items.push(error::number(s, item)?);
                 }
             }
             ["Operation", .., "old", "*", "old"] => {
                 monkeys[current].op = Op::SQUARED;
             }
             ["Operation", .., "old", "*", num] => {
-                monkeys[current].op = Op::MULT(num.parse().unwrap());
+                monkeys[current].op = Op::MULT(error::number(s, num)?);
             }
             ["Operation", .., "old", "+", num] => {
-                monkeys[current].op = Op::PLUS(num.parse().unwrap())
+                monkeys[current].op = Op::PLUS(error::number(s, num)?)
             }
             ["Test", _, "divisible", "by", num] => {
-                monkeys[current].divisible_by = num.parse().unwrap();
+                monkeys[current].divisible_by = error::number(s, num)?;
             }
             ["If", "true", .., num] => {
-                monkeys[current].on_true = num.parse().unwrap();
+                monkeys[current].on_true = error::number(s, num)?;
             }
             ["If", "false", .., num] => {
-                monkeys[current].on_false = num.parse().unwrap();
+                monkeys[current].on_false = error::number(s, num)?;
             }
             [""] => {}
-            _ => panic!(),
+            _ => return Err(ParseError::at(s, line, "unexpected line")),
+        }
+    }
+
+    // Make sure that the monkeys only throw to each other
+    for monkey in &monkeys {
+        if monkey.divisible_by == 0 || monkey.on_true.max(monkey.on_false) >= monkeys.len() {
+            return Err(ParseError::eof(
+                s,
+                &format!("monkey {} is incomplete", monkey.num),
+            ));
         }
     }
-    monkeys
+    Ok(monkeys)
 }
 
 fn simulate(monkeys: &Vec<Monkey>, rounds: i32, part1: bool) -> i64 {
@@ -75,7 +90,7 @@ fn simulate(monkeys: &Vec<Monkey>, rounds: i32, part1: bool) -> i64 {
         count.push(0);
         items.push(monkeys[i].items.clone());
         if !part1 {
-            lcd0 *= monkeys[i].divisible_by;
+            lcd0 = num::integer::lcm(lcd0, monkeys[i].divisible_by);
         }
     }
 
@@ -133,10 +148,22 @@ fn simulate(monkeys: &Vec<Monkey>, rounds: i32, part1: bool) -> i64 {
     (a * b) as i64
 }
 
-pub fn solve() -> (i64, i64) {
-    let buf = include_bytes!("../inputs/input11.txt");
-    let monkeys = parse(buf);
-    let p1 = simulate(&monkeys, 20, true);
-    let p2 = simulate(&monkeys, 10000, false);
-    (p1, p2)
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(buf: &[u8]) -> ParseResult<Vec<Monkey>> {
+        parse(buf)
+    }
+
+    fn part1(monkeys: &Vec<Monkey>) -> i64 {
+        simulate(monkeys, 20, true)
+    }
+
+    fn part2(monkeys: &Vec<Monkey>) -> i64 {
+        simulate(monkeys, 10000, false)
+    }
 }
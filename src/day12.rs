@@ -1,13 +1,13 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 use std::collections::{BTreeSet, HashMap};
 
-const WIDTH: usize = 64;
-const HEIGHT: usize = 40;
 const LOWEST_ELEV: i32 = 'a' as i32;
 const HIGHEST_ELEV: i32 = 'z' as i32;
 
 type Coord = (i64, i64);
 
-struct Grid {
+pub struct Grid {
     grid: Vec<Vec<char>>,
     width: usize,
     height: usize,
@@ -16,34 +16,50 @@ struct Grid {
 }
 
 impl Grid {
-    fn new_from_bytes(buf: &[u8]) -> Grid {
+    fn new_from_bytes(buf: &[u8]) -> ParseResult<Grid> {
+        let input = error::as_str(buf)?;
         let mut grid: Vec<Vec<char>> = Vec::new();
-        let mut start: Coord = (0, 0);
-        let mut end: Coord = (0, 0);
-
-        for y in 0..HEIGHT {
+        let mut start: Option<Coord> = None;
+        let mut end: Option<Coord> = None;
+        let width = input
+            .find('\n')
+            .ok_or_else(|| ParseError::eof(input, "expected a newline"))?;
+        let height = input.lines().count();
+
+        for (y, line) in input.lines().enumerate() {
             grid.push(Vec::new());
 
-            assert_eq!('\n', buf[WIDTH] as char);
+            error::check_chars(input, line, |c| {
+                c.is_ascii_lowercase() || c == 'S' || c == 'E'
+            })?;
+            if line.len() != width {
+                return Err(ParseError::at(
+                    input,
+                    line,
+                    "rows must have the same length",
+                ));
+            }
 
-            for x in 0..WIDTH {
-                let c: char = buf[(y * (WIDTH + 1) + x) as usize] as char;
+            for (x, c) in line.chars().enumerate() {
                 grid[y as usize].push(c);
                 if c == 'S' {
-                    start = (x as i64, y as i64);
+                    start = Some((x as i64, y as i64));
                 } else if c == 'E' {
-                    end = (x as i64, y as i64);
+                    end = Some((x as i64, y as i64));
                 }
             }
         }
 
-        Grid {
+        let start = start.ok_or_else(|| ParseError::eof(input, "no start position (S)"))?;
+        let end = end.ok_or_else(|| ParseError::eof(input, "no end position (E)"))?;
+
+        Ok(Grid {
             grid,
-            width: WIDTH,
-            height: HEIGHT,
+            width,
+            height,
             start,
             end,
-        }
+        })
     }
 
     fn is_valid_coord(&self, pos: Coord) -> bool {
@@ -111,8 +127,8 @@ impl Grid {
 
 fn find_all_as(grid: &Grid) -> Vec<Coord> {
     let mut a_vec: Vec<Coord> = Vec::new();
-    for y in 0..HEIGHT {
-        for x in 0..WIDTH {
+    for y in 0..grid.height {
+        for x in 0..grid.width {
             let pos = (x as i64, y as i64);
             if grid.elevation_at(pos) == LOWEST_ELEV {
                 a_vec.push(pos);
@@ -122,12 +138,22 @@ fn find_all_as(grid: &Grid) -> Vec<Coord> {
     a_vec
 }
 
-pub fn solve() -> (i64, i64) {
-    let buf = include_bytes!("../inputs/input12.txt");
-    let grid: Grid = Grid::new_from_bytes(buf);
+pub struct Day12;
 
-    let p1 = grid.find(vec![grid.start]);
-    let p2 = grid.find(find_all_as(&grid));
+impl Solution for Day12 {
+    type Input = Grid;
+    type Part1 = i64;
+    type Part2 = i64;
 
-    (p1, p2)
+    fn parse(buf: &[u8]) -> ParseResult<Grid> {
+        Grid::new_from_bytes(buf)
+    }
+
+    fn part1(grid: &Grid) -> i64 {
+        grid.find(vec![grid.start])
+    }
+
+    fn part2(grid: &Grid) -> i64 {
+        grid.find(find_all_as(grid))
+    }
 }
@@ -1,11 +1,12 @@
-use itertools::Itertools;
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::cmp::Ordering;
 use std::collections::VecDeque;
 
 #[derive(Debug, Eq)]
-enum Packet {
+pub enum Packet {
     LIST(Vec<Packet>),
     INT(i64),
 }
@@ -88,52 +89,100 @@ fn parse(tokens: &mut VecDeque<Token>, packet: &mut Packet) {
     }
 }
 
-fn tokenize_and_parse(line: &str) -> Packet {
+// Checks that the line is a single, well-formed packet
+fn check_packet(input: &str, line: &str) -> ParseResult<()> {
+    error::check_chars(input, line, |c| c.is_ascii_digit() || "[],".contains(c))?;
+    let mut depth = 0;
+    for (i, c) in line.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth == 0 => return Err(ParseError::at(input, &line[i..=i], "unmatched ]")),
+            ']' => depth -= 1,
+            _ if depth == 0 => {
+                return Err(ParseError::at(input, &line[i..=i], "expected ["));
+            }
+            _ => {}
+        }
+        if depth == 0 && i + 1 < line.len() {
+            return Err(ParseError::at(input, &line[i + 1..], "trailing characters"));
+        }
+    }
+    if depth > 0 || line.is_empty() {
+        return Err(ParseError::at(input, &line[line.len()..], "expected ]"));
+    }
+    Ok(())
+}
+
+pub fn tokenize_and_parse(line: &str) -> Packet {
     let mut tokens = tokenize(line);
     let mut root = Packet::LIST(vec![]);
     parse(&mut tokens, &mut root);
     return root;
 }
 
-pub fn solve() -> (i64, i64) {
-    let buf = include_bytes!("../inputs/input13.txt");
-    let mut p1 = 0;
-    let mut index = 1;
+pub struct Day13;
 
-    let divider1 = tokenize_and_parse("[[2]]");
-    let divider2 = tokenize_and_parse("[[6]]");
-    let mut smaller_than_1 = 0;
-    let mut between_1_and_2 = 0;
+impl Solution for Day13 {
+    type Input = Vec<(Packet, Packet)>;
+    type Part1 = i64;
+    type Part2 = i64;
 
-    for (a, b, _) in String::from_utf8_lossy(buf).split("\n").tuples() {
-        let packet_a = tokenize_and_parse(a);
-        let packet_b = tokenize_and_parse(b);
+    // Pairs of packets, separated by empty lines
+    fn parse(buf: &[u8]) -> ParseResult<Vec<(Packet, Packet)>> {
+        let input = error::as_str(buf)?;
+        let pairs = input.trim_end();
+        if pairs.is_empty() {
+            return Ok(vec![]);
+        }
+        pairs
+            .split("\n\n")
+            .map(|pair| {
+                // There is always a first line, even if it is empty
+                let mut lines = pair.split('\n');
+                let a = lines.next().unwrap();
+                match (lines.next(), lines.next()) {
+                    (Some(b), None) => {
+                        check_packet(input, a)?;
+                        check_packet(input, b)?;
+                        Ok((tokenize_and_parse(a), tokenize_and_parse(b)))
+                    }
+                    (Some(_), Some(c)) => Err(ParseError::at(input, c, "expected an empty line")),
+                    (None, _) => Err(ParseError::at(input, a, "packet without a pair")),
+                }
+            })
+            .collect()
+    }
 
-        if packet_a <= packet_b {
-            p1 += index;
+    fn part1(pairs: &Vec<(Packet, Packet)>) -> i64 {
+        let mut p1 = 0;
+        for (index, (packet_a, packet_b)) in pairs.iter().enumerate() {
+            if packet_a <= packet_b {
+                p1 += index as i64 + 1;
+            }
         }
+        p1
+    }
+
+    fn part2(pairs: &Vec<(Packet, Packet)>) -> i64 {
+        let divider1 = tokenize_and_parse("[[2]]");
+        let divider2 = tokenize_and_parse("[[6]]");
+        let mut smaller_than_1 = 0;
+        let mut between_1_and_2 = 0;
 
         // For part 2, we don't need to sort everything, just keep track of
         // how many packets are before/after the divider packets.
-        if packet_a < divider1 {
-            smaller_than_1 += 1;
-        } else if packet_a < divider2 {
-            between_1_and_2 += 1;
-        }
-
-        if packet_b < divider1 {
-            smaller_than_1 += 1;
-        } else if packet_b < divider2 {
-            between_1_and_2 += 1;
+        for packet in pairs.iter().flat_map(|(a, b)| [a, b]) {
+            if *packet < divider1 {
+                smaller_than_1 += 1;
+            } else if *packet < divider2 {
+                between_1_and_2 += 1;
+            }
         }
 
-        index += 1;
+        let divider_index1 = smaller_than_1 + 1;
+        let divider_index2 = divider_index1 + between_1_and_2 + 1;
+        divider_index1 * divider_index2
     }
-
-    let divider_index1 = smaller_than_1 + 1;
-    let divider_index2 = divider_index1 + between_1_and_2 + 1;
-    let p2 = divider_index1 * divider_index2;
-    (p1, p2)
 }
 
 #[cfg(test)]
@@ -1,10 +1,13 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::{Parts, Solution};
+use crate::visualize::{Frame, Frames, Visualize};
 use grid::*;
 use itertools::Itertools;
 use std::cmp::*;
 use std::ops::RangeInclusive;
 
 #[derive(Eq, PartialEq, Clone, Debug, Default)]
-enum Cell {
+pub enum Cell {
     WALL,
     SAND,
     #[default]
@@ -13,26 +16,36 @@ enum Cell {
 
 type Part = i32;
 type Coord = (usize, usize);
-type CellGrid = Grid<Cell>;
-
-// Parses a string "number_a -> number_b" into a tuple of (i32, i32)
-fn str_to_coord(s: &str) -> Coord {
-    s.split(",")
-        .map(|c| c.parse::<usize>().unwrap())
-        .next_tuple()
-        .unwrap()
+pub type CellGrid = Grid<Cell>;
+
+// Parses a string "x,y" into a tuple of (x, y)
+fn str_to_coord(input: &str, s: &str) -> ParseResult<Coord> {
+    let mut parts = s.split(",");
+    let x = error::next_number(input, s, parts.next())?;
+    let y = error::next_number(input, s, parts.next())?;
+    Ok((x, y))
 }
 
-fn parse_grid(buf: &[u8]) -> (CellGrid, usize) {
+fn parse_grid(buf: &[u8]) -> ParseResult<(CellGrid, usize)> {
+    let input = error::as_str(buf)?;
     let rows = 200;
     let cols = 700;
     let mut grid = CellGrid::new(rows, cols);
     let mut max_y = 0;
 
-    for line in String::from_utf8_lossy(buf).trim().split("\n") {
+    // The floor in part 2 is two rows below the lowest wall
+    let check = |s: &str, (x, y): Coord| {
+        if y + 2 >= rows || x >= cols {
+            Err(ParseError::at(input, s, "coordinate is outside the cave"))
+        } else {
+            Ok((x, y))
+        }
+    };
+
+    for line in input.trim().split("\n") {
         for (a, b) in line.split(" -> ").tuple_windows() {
-            let (x1, y1) = &str_to_coord(a);
-            let (x2, y2) = &str_to_coord(b);
+            let (x1, y1) = &check(a, str_to_coord(input, a)?)?;
+            let (x2, y2) = &check(b, str_to_coord(input, b)?)?;
 
             for x in range(x1, x2) {
                 for y in range(y1, y2) {
@@ -43,7 +56,7 @@ fn parse_grid(buf: &[u8]) -> (CellGrid, usize) {
         }
     }
 
-    (grid, max_y)
+    Ok((grid, max_y))
 }
 
 fn range(x1: &usize, x2: &usize) -> RangeInclusive<usize> {
@@ -54,54 +67,108 @@ fn range(x1: &usize, x2: &usize) -> RangeInclusive<usize> {
     }
 }
 
+// Drops one unit of sand from `start`, and returns where it comes to rest,
+// or None if it falls into the abyss (part 1)
+fn drop_sand(grid: &mut CellGrid, start: Coord, max_y: usize, part: Part) -> Option<Coord> {
+    let (mut x, mut y) = start;
+    loop {
+        if y > max_y && part == 1 {
+            return None;
+        } else if y == max_y + 1 && part == 2 {
+            break;
+        } else if grid[y + 1][x] == Cell::EMPTY {
+            y += 1;
+        } else if grid[y + 1][x - 1] == Cell::EMPTY {
+            (x, y) = (x - 1, y + 1);
+        } else if grid[y + 1][x + 1] == Cell::EMPTY {
+            (x, y) = (x + 1, y + 1);
+        } else {
+            break;
+        }
+    }
+    grid[y][x] = Cell::SAND;
+    Some((x, y))
+}
+
 fn simulate(start: Coord, grid_orig: &CellGrid, max_y: usize, part: Part) -> usize {
     let mut grid = grid_orig.clone();
-    let mut current = start.clone();
     let mut num_units = 0;
-    loop {
-        match current {
-            (_, y) if y > max_y && part == 1 => {
-                return num_units;
+    // In part 2, we stop when the sand blocks the source
+    while let Some(pos) = drop_sand(&mut grid, start, max_y, part) {
+        num_units += 1;
+        if pos == start {
+            break;
+        }
+    }
+    num_units
+}
+
+const START: Coord = (500, 0);
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = (CellGrid, usize);
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(buf: &[u8]) -> ParseResult<(CellGrid, usize)> {
+        parse_grid(buf)
+    }
+
+    fn part1((grid, max_y): &(CellGrid, usize)) -> usize {
+        simulate(START, grid, *max_y, 1)
+    }
+
+    fn part2((grid, max_y): &(CellGrid, usize)) -> usize {
+        simulate(START, grid, *max_y, 2)
+    }
+}
+
+// The cave after each unit of sand comes to rest
+impl Visualize for Day14 {
+    fn frames((mut grid, max_y): (CellGrid, usize), parts: Parts) -> Result<Frames, String> {
+        let part = if parts == Parts::Part2 { 2 } else { 1 };
+        let walls = (0..grid.cols()).filter(|x| (0..=max_y).any(|y| grid[y][*x] == Cell::WALL));
+        let (min_x, max_x) = match walls.minmax().into_option() {
+            Some((min_x, max_x)) if part == 1 => (min_x.saturating_sub(1), max_x + 1),
+            // The pile on the floor spreads as wide as it is high
+            _ => (START.0 - max_y - 3, START.0 + max_y + 3),
+        };
+        let max_x = max_x.min(grid.cols() - 1);
+        let mut num_units = 0;
+        let mut last: Option<Coord> = None;
+        let mut started = false;
+        let mut done = false;
+
+        Ok(Box::new(std::iter::from_fn(move || {
+            if done {
+                return None;
             }
-            (x, y) if y == max_y + 1 && part == 2 => {
-                grid[y][x] = Cell::SAND;
+            if started {
+                let pos = drop_sand(&mut grid, START, max_y, part)?;
                 num_units += 1;
-                current = start;
+                done = pos == START;
+                last = Some(pos);
             }
-            pos @ (x, y) => match grid[y + 1][x] {
-                Cell::EMPTY => {
-                    current = (x, y + 1);
-                }
-                _ => match grid[y + 1][x - 1] {
-                    Cell::EMPTY => {
-                        current = (x - 1, y + 1);
-                    }
-                    _ => match grid[y + 1][x + 1] {
-                        Cell::EMPTY => {
-                            current = (x + 1, y + 1);
-                        }
-                        _ if pos == start && part == 2 => {
-                            grid[y][x] = Cell::SAND;
-                            num_units += 1;
-                            return num_units;
-                        }
-                        _ => {
-                            grid[y][x] = Cell::SAND;
-                            num_units += 1;
-                            current = start;
-                        }
+            started = true;
+            let frame = Frame::draw(
+                format!("{} units of sand", num_units),
+                max_x - min_x + 1,
+                max_y + 3,
+                |col, y| match (col + min_x, y) {
+                    pos if Some(pos) == last => '@',
+                    pos if pos == START => '+',
+                    (_, y) if y == max_y + 2 && part == 2 => '#',
+                    (x, y) => match grid[y][x] {
+                        Cell::WALL => '#',
+                        Cell::SAND => 'o',
+                        Cell::EMPTY => '.',
                     },
                 },
-            },
-        }
+            );
+            let (x, y) = last.unwrap_or(START);
+            Some(frame.with_focus(x - min_x, y))
+        })))
     }
 }
-
-pub fn solve() -> (usize, usize) {
-    let buf = include_bytes!("../inputs/input14.txt");
-    let (grid, max_y) = parse_grid(buf);
-    let start = (500, 0);
-    let p1 = simulate(start, &grid, max_y, 1);
-    let p2 = simulate(start, &grid, max_y, 2);
-    (p1, p2)
-}
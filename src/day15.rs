@@ -1,44 +1,48 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 use lazy_regex::regex_captures;
 use std::collections::{HashMap, HashSet};
 
 // This actually needs to be i128, i64 is not enough
-type IntType = i128;
-type Coord = (IntType, IntType);
-type Line = (Coord, Coord);
-type Interval = (IntType, IntType);
+pub type IntType = i128;
+pub type Coord = (IntType, IntType);
+pub type Line = (Coord, Coord);
+pub type Interval = (IntType, IntType);
 
 struct SensorInfo {
     sensor: Coord,
     dist: IntType,
 }
 
-struct Input {
+// The row to check in part 1, for real inputs
+pub const ROW: IntType = 2000000;
+
+pub struct Input {
     sensor_infos: Vec<SensorInfo>,
     sensors: HashSet<Coord>,
     beacons: HashSet<Coord>,
+    // The row to check in part 1. The input does not say which, so scaled
+    // down inputs (like the example, which checks row 10) have to set it.
+    pub row: IntType,
 }
 
-fn parse(buf: &[u8]) -> Input {
+pub fn parse(buf: &[u8]) -> ParseResult<Input> {
+    let text = error::as_str(buf)?;
     let mut input = Input {
         sensor_infos: Vec::new(),
         sensors: HashSet::new(),
         beacons: HashSet::new(),
+        row: ROW,
     };
 
-    for line in String::from_utf8_lossy(buf).trim().split("\n") {
+    for line in text.trim().split("\n") {
         let (_, sx, sy, bx, by) = regex_captures!(
             r#"Sensor at x=(.*), y=(.*): closest beacon is at x=(.*), y=(.*)"#,
             line
         )
-        .unwrap();
-        let sensor = (
-            sx.parse::<IntType>().unwrap(),
-            sy.parse::<IntType>().unwrap(),
-        );
-        let beacon = (
-            bx.parse::<IntType>().unwrap(),
-            by.parse::<IntType>().unwrap(),
-        );
+        .ok_or_else(|| ParseError::at(text, line, "expected a sensor and its closest beacon"))?;
+        let sensor = (error::number(text, sx)?, error::number(text, sy)?);
+        let beacon = (error::number(text, bx)?, error::number(text, by)?);
         input.beacons.insert(beacon);
         input.sensors.insert(sensor);
         input.sensor_infos.push(SensorInfo {
@@ -47,7 +51,7 @@ fn parse(buf: &[u8]) -> Input {
         });
     }
 
-    input
+    Ok(input)
 }
 
 fn dist(sensor: Coord, beacon: Coord) -> IntType {
@@ -100,7 +104,7 @@ fn is_valid_y(y: IntType, line: Line) -> bool {
     }
 }
 
-fn intersects(line1: Line, line2: Line) -> Option<Coord> {
+pub fn intersects(line1: Line, line2: Line) -> Option<Coord> {
     let (a, b) = line1;
     let (c, d) = line2;
     let (ax, ay) = a;
@@ -140,7 +144,7 @@ fn intersects(line1: Line, line2: Line) -> Option<Coord> {
     }
 }
 
-fn count_intervals(intervals: &mut Vec<Interval>) -> IntType {
+pub fn count_intervals(intervals: &mut Vec<Interval>) -> IntType {
     let n = intervals.len();
     let mut count = 0;
     let mut i = 0;
@@ -174,7 +178,7 @@ fn count_intervals(intervals: &mut Vec<Interval>) -> IntType {
     count
 }
 
-fn part1(input: &Input) -> IntType {
+pub fn part1(input: &Input) -> IntType {
     let x_min = input
         .sensor_infos
         .iter()
@@ -189,7 +193,7 @@ fn part1(input: &Input) -> IntType {
         .max()
         .unwrap();
 
-    let y = 2000000;
+    let y = input.row;
     let line = ((x_min, y), (x_max, y));
     let mut intervals: Vec<Interval> = vec![];
 
@@ -232,7 +236,7 @@ fn all_perimeter_lines(input: &Input) -> Vec<Line> {
     return pl;
 }
 
-fn part2(input: &Input) -> IntType {
+pub fn part2(input: &Input) -> IntType {
     let pl = all_perimeter_lines(input);
     let mut pairs: Vec<(Line, Line)> = vec![];
     let mut freq: HashMap<Coord, IntType> = HashMap::new();
@@ -273,10 +277,22 @@ fn is_in_range_of_any_sensor(pos: (i128, i128), sensor_infos: &[SensorInfo]) ->
     return false;
 }
 
-pub fn solve() -> (IntType, IntType) {
-    let buf = include_bytes!("../inputs/input15.txt");
-    let input = parse(buf);
-    let p1 = part1(&input);
-    let p2 = part2(&input);
-    (p1, p2)
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Input;
+    type Part1 = IntType;
+    type Part2 = IntType;
+
+    fn parse(buf: &[u8]) -> ParseResult<Input> {
+        parse(buf)
+    }
+
+    fn part1(input: &Input) -> IntType {
+        part1(input)
+    }
+
+    fn part2(input: &Input) -> IntType {
+        part2(input)
+    }
 }
@@ -1,3 +1,5 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 use fnv::FnvHashMap; // faster hashmap
 use grid::Grid;
 use lazy_regex::regex_captures;
@@ -27,7 +29,68 @@ type Graph = Vec<Vec<usize>>;
 type DistGrid = Grid<i64>;
 type AnswerMap = FnvHashMap<Bitmask, i64>;
 
-pub fn solve() -> (i64, i64) {
+pub struct Input {
+    start: usize,
+    flows: Flows,
+    dists: DistGrid,
+    indices: Indices,
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Input;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(buf: &[u8]) -> ParseResult<Input> {
+        parse(buf)
+    }
+
+    fn part1(input: &Input) -> i64 {
+        let mut answers_p1 = FnvHashMap::default();
+        visit(
+            input.start,
+            30,
+            0,
+            0,
+            &mut answers_p1,
+            &input.flows,
+            &input.dists,
+            &input.indices,
+        );
+        *answers_p1.values().max().unwrap()
+    }
+
+    fn part2(input: &Input) -> i64 {
+        let mut answers_p2 = FnvHashMap::default();
+        visit(
+            input.start,
+            26,
+            0,
+            0,
+            &mut answers_p2,
+            &input.flows,
+            &input.dists,
+            &input.indices,
+        );
+        let mut p2 = 0;
+        for (k1, v1) in &answers_p2 {
+            for (k2, v2) in &answers_p2 {
+                if k1 & k2 == 0 {
+                    let maxflow = v1 + v2;
+                    if maxflow > p2 {
+                        p2 = maxflow;
+                    }
+                }
+            }
+        }
+        p2
+    }
+}
+
+// Parses the valve graph and computes the distances between all valves
+fn parse(buf: &[u8]) -> ParseResult<Input> {
     const NUM_LETTERS: usize = 26;
     const NUM_VALVES: usize = NUM_LETTERS * NUM_LETTERS;
 
@@ -36,38 +99,44 @@ pub fn solve() -> (i64, i64) {
     // an arbitrary integer.
     let mut idx: usize = 0;
     let mut valve_map: FnvHashMap<String, usize> = FnvHashMap::default();
-    let mut start: usize = 0;
+    let mut start: Option<usize> = None;
 
-    let buf = include_bytes!("../inputs/input16.txt");
-    let mut graph_pre: Vec<String> = Vec::new();
+    let mut graph_pre: Vec<&str> = Vec::new();
     let mut graph: Graph = Vec::new();
     let mut flows: Flows = FnvHashMap::default();
     let mut indices: Indices = vec![];
     let mut dists: DistGrid = Grid::new(NUM_VALVES, NUM_VALVES);
 
-    for line in String::from_utf8_lossy(buf).trim().split("\n") {
+    let input = error::as_str(buf)?;
+    for line in input.trim().split("\n") {
         let (_, valve, flow_rate, leads_to) = regex_captures!(
             r#"Valve (.*) has flow rate=(.*); tunnels? leads? to valves? (.*)"#,
             line
         )
-        .unwrap();
+        .ok_or_else(|| ParseError::at(input, line, "expected a valve description"))?;
+
+        // Valves are kept in a bitmask
+        if idx >= Bitmask::BITS as usize - 1 {
+            return Err(ParseError::at(input, line, "too many valves"));
+        }
 
         valve_map.insert(valve.to_string(), idx);
         if valve == "AA" {
-            start = idx;
+            start = Some(idx);
         }
 
         indices.push(1 << idx);
 
-        let fr = flow_rate.parse::<i64>().unwrap();
+        let fr = error::number::<i64>(input, flow_rate)?;
         if fr > 0 {
             flows.insert(idx, fr);
         }
 
-        graph_pre.push(leads_to.to_string());
+        graph_pre.push(leads_to);
         idx += 1;
     }
 
+    let start = start.ok_or_else(|| ParseError::eof(input, "no valve AA"))?;
     let num_valves = idx;
 
     // Build the graph now that all valves have been mapped to integers
@@ -75,8 +144,14 @@ pub fn solve() -> (i64, i64) {
         graph.push(
             graph_pre[i]
                 .split(',')
-                .map(|s| *valve_map.get(s.to_string().trim()).unwrap())
-                .collect::<Vec<usize>>(),
+                .map(|s| {
+                    let name = s.trim();
+                    valve_map
+                        .get(name)
+                        .copied()
+                        .ok_or_else(|| ParseError::at(input, name, "unknown valve"))
+                })
+                .collect::<ParseResult<Vec<usize>>>()?,
         );
     }
 
@@ -102,25 +177,12 @@ pub fn solve() -> (i64, i64) {
         }
     }
 
-    let mut answers_p1 = FnvHashMap::default();
-    visit(start, 30, 0, 0, &mut answers_p1, &flows, &dists, &indices);
-    let p1 = *answers_p1.values().max().unwrap();
-
-    let mut answers_p2 = FnvHashMap::default();
-    visit(start, 26, 0, 0, &mut answers_p2, &flows, &dists, &indices);
-    let mut p2 = 0;
-    for (k1, v1) in &answers_p2 {
-        for (k2, v2) in &answers_p2 {
-            if k1 & k2 == 0 {
-                let maxflow = v1 + v2;
-                if maxflow > p2 {
-                    p2 = maxflow;
-                }
-            }
-        }
-    }
-
-    (p1, p2)
+    Ok(Input {
+        start,
+        flows,
+        dists,
+        indices,
+    })
 }
 
 fn visit(
@@ -1,3 +1,6 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::{Parts, Solution};
+use crate::visualize::{Frame, Frames, Visualize};
 use std::collections::HashMap;
 
 type ChamberCoord = (i32, i32);
@@ -180,29 +183,98 @@ impl CycleTracker {
     }
 }
 
-pub fn solve() -> (i32, u64) {
-    let jets = include_bytes!("../inputs/input17.txt");
-    let mut chamber = Chamber::new(jets);
-    let mut cycle_tracker = CycleTracker::new();
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Vec<u8>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(buf: &[u8]) -> ParseResult<Vec<u8>> {
+        let input = error::as_str(buf)?;
+        let jets = input.trim_end();
+        error::check_chars(input, jets, |c| c == '<' || c == '>')?;
+        if jets.is_empty() {
+            return Err(ParseError::eof(input, "no jets"));
+        }
+        // The jets are expected to end with a newline
+        Ok(format!("{}\n", jets).into_bytes())
+    }
 
     // For p1, we just drop 2022 rocks
-    let mut p1: i32 = 0;
-    let p1_limit = 2022;
+    fn part1(jets: &Vec<u8>) -> u64 {
+        tower_height(jets, 2022, false)
+    }
 
     // For p2, we drop 1 trillion rocks. We keep track of cycles
     // so that we do not need to actually drop the rocks which are part of
     // (full) cycles.
-    let mut track_cycles = true;
-    let mut p2_limit: u64 = 1_000_000_000_000;
+    fn part2(jets: &Vec<u8>) -> u64 {
+        tower_height(jets, 1_000_000_000_000, true)
+    }
+}
+
+// Rows of the chamber shown in each frame
+const VIEW_ROWS: i32 = 30;
+
+// The top of the tower after each of the rocks in part 1 comes to rest. The
+// last rock is drawn as '@'.
+impl Visualize for Day17 {
+    fn frames(jets: Vec<u8>, _parts: Parts) -> Result<Frames, String> {
+        let mut chamber = Chamber::new(&jets);
+        let mut last: Vec<ChamberCoord> = vec![];
+
+        Ok(Box::new((0..=2022).map(move |rock_num| {
+            if rock_num > 0 {
+                let mut rock = chamber.next_rock();
+                loop {
+                    let jet = chamber.next_jet();
+                    chamber.maybe_move_sideways(&mut rock, jet);
+                    if !chamber.maybe_drop(&mut rock) {
+                        break;
+                    }
+                }
+                last = rock
+                    .coords
+                    .iter()
+                    .map(|coord| rock.rock_to_chamber_coords(*coord, (0, 0)))
+                    .collect();
+                chamber.add_to_tower(rock);
+            }
+
+            let top = chamber.height.max(VIEW_ROWS);
+            let bottom = top - VIEW_ROWS;
+            Frame::draw(
+                format!("{} rocks, height {}", rock_num, chamber.height),
+                9,
+                VIEW_ROWS as usize + 1,
+                |col, row| {
+                    let y = top - 1 - row as i32;
+                    match (col, y) {
+                        (0 | 8, y) if y < bottom && bottom == 0 => '+',
+                        (_, y) if y < bottom && bottom == 0 => '-',
+                        (0 | 8, _) => '|',
+                        (_, y) if y < bottom => '~',
+                        (x, y) if last.contains(&(x as i32 - 1, y)) => '@',
+                        (x, y) if chamber.is_chamber_coord_part_of_tower(&(x as i32 - 1, y)) => '#',
+                        _ => '.',
+                    }
+                },
+            )
+        })))
+    }
+}
+
+fn tower_height(jets: &[u8], num_rocks: u64, mut track_cycles: bool) -> u64 {
+    let mut chamber = Chamber::new(jets);
+    let mut cycle_tracker = CycleTracker::new();
+    let mut limit: u64 = num_rocks;
     let mut cycle_height: u64 = 0;
     let mut num_cycles: u64 = 0;
 
     for rock_num in 0.. {
-        if rock_num == p1_limit {
-            p1 = chamber.height;
-        } else if rock_num == p2_limit {
-            let p2 = chamber.height as u64 + (num_cycles * cycle_height);
-            return (p1, p2);
+        if rock_num == limit {
+            return chamber.height as u64 + (num_cycles * cycle_height);
         }
 
         let mut rock = chamber.next_rock();
@@ -218,12 +290,13 @@ pub fn solve() -> (i32, u64) {
                 if track_cycles {
                     if let Some((start, height)) = cycle_tracker.add_state(&jet, &chamber, rock_num)
                     {
-                        // Reduce the p2 limit by a whole number of cycles, and then calculate
+                        // Reduce the limit by a whole number of cycles, and then calculate
                         // the total height contributed by the rocks which are part of the cycles.
+                        // The rocks up to and including this one have been dropped already.
                         cycle_height = (chamber.height - height) as u64;
                         let rocks_per_cycle = rock_num - start;
-                        num_cycles = (p2_limit - start).div_floor(rocks_per_cycle) - 1;
-                        p2_limit -= num_cycles * rocks_per_cycle;
+                        num_cycles = (limit - rock_num - 1).div_floor(rocks_per_cycle);
+                        limit -= num_cycles * rocks_per_cycle;
                         track_cycles = false;
                     }
                 }
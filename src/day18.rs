@@ -1,6 +1,7 @@
+use crate::error::{self, ParseResult};
+use crate::solution::Solution;
 use std::collections::HashSet;
 
-use itertools::Itertools;
 use queues::*;
 
 type Cube = (i32, i32, i32);
@@ -11,9 +12,24 @@ fn adjacent(c1: &Cube, c2: &Cube) -> bool {
     (a - d).abs() + (b - e).abs() + (c - f).abs() == 1
 }
 
-fn maybe_enqueue(c: &Cube, queue: &mut Queue<Cube>) {
+// The box around all the lava, with room for a layer of water on each side
+fn bounds(coords: &Vec<Cube>) -> (Cube, Cube) {
+    let mut lo = (i32::MAX, i32::MAX, i32::MAX);
+    let mut hi = (i32::MIN, i32::MIN, i32::MIN);
+    for (x, y, z) in coords {
+        lo = (lo.0.min(x - 1), lo.1.min(y - 1), lo.2.min(z - 1));
+        hi = (hi.0.max(x + 1), hi.1.max(y + 1), hi.2.max(z + 1));
+    }
+    (lo, hi)
+}
+
+fn in_bounds(c: &Cube, (lo, hi): &(Cube, Cube)) -> bool {
     let (x, y, z) = c;
-    if *x < -1 || *x > 20 || *y < -1 || *y > 20 || *y < -1 || *z > 20 {
+    (lo.0..=hi.0).contains(x) && (lo.1..=hi.1).contains(y) && (lo.2..=hi.2).contains(z)
+}
+
+fn maybe_enqueue(c: &Cube, bounds: &(Cube, Cube), queue: &mut Queue<Cube>) {
+    if !in_bounds(c, bounds) {
         // out of bounds
         return;
     }
@@ -23,13 +39,14 @@ fn maybe_enqueue(c: &Cube, queue: &mut Queue<Cube>) {
 
 fn fill(coords: &Vec<Cube>) -> i32 {
     let lava: HashSet<Cube> = HashSet::from_iter(coords.iter().cloned());
+    let bounds = bounds(coords);
     let mut area = 0;
     let mut water: HashSet<Cube> = HashSet::new();
-    let mut queue: Queue<Cube> = queue![(0, 0, 0)];
+    let mut queue: Queue<Cube> = queue![bounds.0];
 
     while let Ok(c) = queue.remove() {
         let (x, y, z) = c;
-        if x < -1 || x > 20 || y < -1 || y > 20 || z < -1 || z > 20 {
+        if !in_bounds(&c, &bounds) {
             continue;
         } else if lava.contains(&c) {
             area += 1;
@@ -37,47 +54,55 @@ fn fill(coords: &Vec<Cube>) -> i32 {
             continue;
         } else {
             water.insert(c);
-            maybe_enqueue(&(x + 1, y, z), &mut queue);
-            maybe_enqueue(&(x - 1, y, z), &mut queue);
-            maybe_enqueue(&(x, y + 1, z), &mut queue);
-            maybe_enqueue(&(x, y - 1, z), &mut queue);
-            maybe_enqueue(&(x, y, z + 1), &mut queue);
-            maybe_enqueue(&(x, y, z - 1), &mut queue);
+            maybe_enqueue(&(x + 1, y, z), &bounds, &mut queue);
+            maybe_enqueue(&(x - 1, y, z), &bounds, &mut queue);
+            maybe_enqueue(&(x, y + 1, z), &bounds, &mut queue);
+            maybe_enqueue(&(x, y - 1, z), &bounds, &mut queue);
+            maybe_enqueue(&(x, y, z + 1), &bounds, &mut queue);
+            maybe_enqueue(&(x, y, z - 1), &bounds, &mut queue);
         }
     }
 
     area
 }
 
-pub fn solve() -> (usize, i32) {
-    let bytes = include_bytes!("../inputs/input18.txt");
-    let s = String::from_utf8_lossy(bytes);
-
-    let coords: Vec<Cube> = s
-        .trim()
-        .split("\n")
-        .map(|line| {
-            line.split(",")
-                .into_iter()
-                .map(|s| s.parse().unwrap())
-                .next_tuple::<Cube>()
-                .unwrap()
-        })
-        .collect();
-
-    let mut total_surface_area = coords.len() * 6;
-
-    for c1 in &coords {
-        for c2 in &coords {
-            if c1 < c2 {
-                if adjacent(&c1, &c2) {
-                    total_surface_area -= 2;
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<Cube>;
+    type Part1 = usize;
+    type Part2 = i32;
+
+    fn parse(bytes: &[u8]) -> ParseResult<Vec<Cube>> {
+        let input = error::as_str(bytes)?;
+        input
+            .trim()
+            .split("\n")
+            .map(|line| {
+                let mut parts = line.split(",");
+                let mut coord = || error::next_number(input, line, parts.next());
+                Ok((coord()?, coord()?, coord()?))
+            })
+            .collect()
+    }
+
+    fn part1(coords: &Vec<Cube>) -> usize {
+        let mut total_surface_area = coords.len() * 6;
+
+        for c1 in coords {
+            for c2 in coords {
+                if c1 < c2 {
+                    if adjacent(&c1, &c2) {
+                        total_surface_area -= 2;
+                    }
                 }
             }
         }
-    }
 
-    let area = fill(&coords);
+        total_surface_area
+    }
 
-    (total_surface_area, area)
+    fn part2(coords: &Vec<Cube>) -> i32 {
+        fill(coords)
+    }
 }
@@ -1,8 +1,10 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 use lazy_regex::regex_captures;
 use rayon::prelude::*;
 
 #[derive(Debug)]
-struct Blueprint {
+pub struct Blueprint {
     nr: i32,
     ore_robot_ore_c: i32,
     clay_robot_ore_c: i32,
@@ -21,7 +23,7 @@ const SKIP_CLAY: i32 = 0b010;
 const SKIP_OBS: i32 = 0b100;
 
 impl Blueprint {
-    fn from_str(text: &str) -> Self {
+    fn from_str(input: &str, text: &str) -> ParseResult<Self> {
         let (
             _,
             nr,
@@ -38,17 +40,17 @@ impl Blueprint {
             Each geode robot costs (\\d+) ore and (\\d+) obsidian.",
             text
         )
-        .unwrap();
+        .ok_or_else(|| ParseError::at(input, text, "expected a blueprint"))?;
 
-        let nr = nr.parse().unwrap();
-        let ore_robot_ore_c = ore_robot_ore_c.parse().unwrap();
-        let clay_robot_ore_c = clay_robot_ore_c.parse().unwrap();
-        let obs_robot_ore_c = obs_robot_ore_c.parse().unwrap();
-        let obs_robot_clay_c = obs_robot_clay_c.parse().unwrap();
-        let geo_robot_ore_c = geo_robot_ore_c.parse().unwrap();
-        let geo_robot_obs_c = geo_robot_obs_c.parse().unwrap();
+        let nr = error::number(input, nr)?;
+        let ore_robot_ore_c = error::number(input, ore_robot_ore_c)?;
+        let clay_robot_ore_c = error::number(input, clay_robot_ore_c)?;
+        let obs_robot_ore_c = error::number(input, obs_robot_ore_c)?;
+        let obs_robot_clay_c = error::number(input, obs_robot_clay_c)?;
+        let geo_robot_ore_c = error::number(input, geo_robot_ore_c)?;
+        let geo_robot_obs_c = error::number(input, geo_robot_obs_c)?;
 
-        Blueprint {
+        Ok(Blueprint {
             nr,
             ore_robot_ore_c,
             clay_robot_ore_c,
@@ -62,14 +64,15 @@ impl Blueprint {
             // little bit without actually losing any optimal solutions.
             max_clay_r: obs_robot_clay_c,
             max_obs_r: geo_robot_obs_c,
-        }
+        })
     }
 
-    fn parse_blueprints(text: &[u8]) -> Vec<Blueprint> {
-        String::from_utf8_lossy(text)
+    fn parse_blueprints(text: &[u8]) -> ParseResult<Vec<Blueprint>> {
+        let input = error::as_str(text)?;
+        input
             .trim()
             .split("\n")
-            .map(|line| Blueprint::from_str(line))
+            .map(|line| Blueprint::from_str(input, line))
             .collect()
     }
 }
@@ -231,31 +234,30 @@ fn search(bp: &Blueprint, minutes_left: i32) -> i32 {
     )
 }
 
-pub fn solve() -> (i32, i32) {
-    let input_bytes = include_bytes!("../inputs/input19.txt");
-    let blueprints = Blueprint::parse_blueprints(input_bytes);
+pub struct Day19;
 
-    // Check all blueprints in parallel, then collect the results. Note that
-    // we run all (both p1 and p2) blueprints in parallel.
-    blueprints
-        .iter()
-        .map(|bp| (true, bp))
-        .chain(
-            blueprints
-                .iter()
-                .filter(|bp| bp.nr <= 3)
-                .map(|bp| (false, bp)),
-        )
-        .par_bridge()
-        .into_par_iter()
-        .map(|(part, bp)| match part {
-            true => (part, search(&bp, 24) * bp.nr),
-            false => (part, search(&bp, 32)),
-        })
-        .collect::<Vec<(bool, i32)>>()
-        .iter()
-        .fold((0, 1), |(p1, p2), (part, sol)| match part {
-            true => (p1 + sol, p2),
-            false => (p1, p2 * sol),
-        })
+impl Solution for Day19 {
+    type Input = Vec<Blueprint>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input_bytes: &[u8]) -> ParseResult<Vec<Blueprint>> {
+        Blueprint::parse_blueprints(input_bytes)
+    }
+
+    // Check all blueprints in parallel, then collect the results
+    fn part1(blueprints: &Vec<Blueprint>) -> i32 {
+        blueprints
+            .par_iter()
+            .map(|bp| search(&bp, 24) * bp.nr)
+            .sum()
+    }
+
+    fn part2(blueprints: &Vec<Blueprint>) -> i32 {
+        blueprints
+            .par_iter()
+            .filter(|bp| bp.nr <= 3)
+            .map(|bp| search(&bp, 32))
+            .product()
+    }
 }
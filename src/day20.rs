@@ -1,5 +1,8 @@
 // day 20
 
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
+
 const DECRYPT_KEY: i64 = 811589153;
 
 fn mix(numbers: &Vec<(usize, i64)>, ring: &mut Vec<(usize, i64)>) {
@@ -34,15 +37,32 @@ fn mix_n(list: &Vec<i64>, n: i32, decrypt_key: i64) -> i64 {
     a.1 + b.1 + c.1
 }
 
-pub fn solve() -> (i64, i64) {
-    let bytes = include_bytes!("../inputs/input20.txt");
-    let numbers: Vec<i64> = String::from_utf8_lossy(bytes)
-        .trim()
-        .split("\n")
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(bytes: &[u8]) -> ParseResult<Vec<i64>> {
+        let input = error::as_str(bytes)?;
+        let numbers = input
+            .trim()
+            .split("\n")
+            .map(|s| error::number(input, s))
+            .collect::<ParseResult<Vec<i64>>>()?;
+        // The grove coordinates are counted from the zero
+        if !numbers.contains(&0) {
+            return Err(ParseError::eof(input, "no zero in the list"));
+        }
+        Ok(numbers)
+    }
+
+    fn part1(numbers: &Vec<i64>) -> i64 {
+        mix_n(numbers, 1, 1)
+    }
 
-    let p1 = mix_n(&numbers, 1, 1);
-    let p2 = mix_n(&numbers, 10, DECRYPT_KEY);
-    (p1, p2)
+    fn part2(numbers: &Vec<i64>) -> i64 {
+        mix_n(numbers, 10, DECRYPT_KEY)
+    }
 }
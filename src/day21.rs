@@ -1,8 +1,10 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 use lazy_regex::regex_captures;
 use std::collections::HashMap;
 
 #[derive(Debug)]
-enum Rule {
+pub enum Rule {
     MathOp(String, char, String),
     Number(i64),
 }
@@ -96,30 +98,60 @@ fn reduce(lhs: Expr, rhs: Expr) -> Expr {
     }
 }
 
-pub fn solve() -> (i64, i64) {
-    let bytes = include_bytes!("../inputs/input21.txt");
-    let rules = String::from_utf8_lossy(bytes)
-        .trim()
-        .split("\n")
-        .map(|line| {
-            if let Some((_, m, lhs, op, rhs)) = regex_captures!(r"(.+): (.+) (.*) (.*)", line) {
-                (
-                    m.to_string(),
-                    Rule::MathOp(lhs.to_string(), op.chars().next().unwrap(), rhs.to_string()),
-                )
-            } else if let Some((_, m, num)) = regex_captures!(r"(.*): (.*)", line) {
-                (m.to_string(), Rule::Number(num.parse::<i64>().unwrap()))
-            } else {
-                unreachable!()
+pub type Rules = HashMap<String, Rule>;
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Rules;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(bytes: &[u8]) -> ParseResult<Rules> {
+        let input = error::as_str(bytes)?;
+        let mut operands = vec![];
+        let rules = input
+            .trim()
+            .split("\n")
+            .map(|line| {
+                if let Some((_, m, lhs, op, rhs)) =
+                    regex_captures!(r"(.+): (.+) ([-+*/]) (.+)", line)
+                {
+                    operands.extend([lhs, rhs]);
+                    Ok((
+                        m.to_string(),
+                        Rule::MathOp(lhs.to_string(), op.chars().next().unwrap(), rhs.to_string()),
+                    ))
+                } else if let Some((_, m, num)) = regex_captures!(r"(.*): (.*)", line) {
+                    Ok((m.to_string(), Rule::Number(error::number(input, num)?)))
+                } else {
+                    Err(ParseError::at(input, line, "expected a monkey job"))
+                }
+            })
+            .collect::<ParseResult<Rules>>()?;
+        if let Some(operand) = operands.iter().find(|m| !rules.contains_key(**m)) {
+            return Err(ParseError::at(input, operand, "no monkey with this name"));
+        }
+        for monkey in ["root", "humn"] {
+            if !rules.contains_key(monkey) {
+                return Err(ParseError::eof(
+                    input,
+                    &format!("no monkey named {}", monkey),
+                ));
             }
-        })
-        .collect::<HashMap<String, Rule>>();
+        }
+        Ok(rules)
+    }
+
+    fn part1(rules: &Rules) -> i64 {
+        yell("root", rules)
+    }
 
-    let p1 = yell("root", &rules);
-    let p2 = if let Expr::Num(num) = solve_for_humn("root", &rules) {
-        num
-    } else {
-        unreachable!();
-    };
-    (p1, p2)
+    fn part2(rules: &Rules) -> i64 {
+        if let Expr::Num(num) = solve_for_humn("root", rules) {
+            num
+        } else {
+            unreachable!();
+        }
+    }
 }
@@ -1,9 +1,9 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::{Parts, Solution};
+use crate::visualize::{Frame, Frames, Visualize};
 use grid::Grid;
 use lazy_regex::regex;
 
-const GRID_COLS: i32 = 150;
-const GRID_ROWS: i32 = 200;
-
 type Direction = u8;
 const RIGHT: u8 = 0;
 const DOWN: u8 = 1;
@@ -11,14 +11,14 @@ const LEFT: u8 = 2;
 const UP: u8 = 3;
 
 #[derive(Debug)]
-enum Instr {
+pub enum Instr {
     Left,
     Right,
     Walk(i32),
 }
 
 #[derive(Debug)]
-struct Pos {
+pub struct Pos {
     row: usize,
     col: usize,
 }
@@ -41,17 +41,18 @@ impl State {
             heading: RIGHT,
         }
     }
-    fn execute(&mut self, instr: &Instr, grid: &Grid<char>, part: i32) {
+    fn execute(&mut self, instr: &Instr, input: &Input, part: i32) {
         match instr {
             Instr::Left => self.heading = (self.heading + 3) % 4,
             Instr::Right => self.heading = (self.heading + 1) % 4,
-            Instr::Walk(steps) => self.walk(steps, grid, part),
+            Instr::Walk(steps) => self.walk(steps, input, part),
         }
     }
 
-    fn walk(&mut self, steps: &i32, grid: &Grid<char>, part: i32) {
+    fn walk(&mut self, steps: &i32, input: &Input, part: i32) {
+        let grid = &input.grid;
         for _ in 0..*steps {
-            if let Some((new_pos, tile)) = get_forward_pos(&self.pos, self.heading, &grid) {
+            if let Some((new_pos, tile)) = get_forward_pos(&self.pos, self.heading, grid) {
                 match tile {
                     '.' => {
                         self.pos = new_pos;
@@ -63,7 +64,7 @@ impl State {
             } else {
                 // Moving one step would move us outside the known grid, so
                 // we need to warp.
-                let (warp_pos, warp_heading) = self.warp_pos(part, grid);
+                let (warp_pos, warp_heading) = self.warp_pos(part, input);
                 match grid[warp_pos.row][warp_pos.col] {
                     '.' => {
                         self.pos = warp_pos;
@@ -77,12 +78,12 @@ impl State {
         }
     }
 
-    fn warp_pos(&mut self, part: i32, grid: &Grid<char>) -> (Pos, Direction) {
+    fn warp_pos(&mut self, part: i32, input: &Input) -> (Pos, Direction) {
         if part == 1 {
             let mut warp_pos = Pos::new(self.pos.row, self.pos.col);
             let warp_heading = (self.heading + 2) % 4; // turn backwards
             loop {
-                if let Some((pos, _tile)) = get_forward_pos(&warp_pos, warp_heading, grid) {
+                if let Some((pos, _tile)) = get_forward_pos(&warp_pos, warp_heading, &input.grid) {
                     warp_pos = pos;
                     continue;
                 } else {
@@ -90,37 +91,163 @@ impl State {
                 }
             }
         } else if part == 2 {
-            // Part 2. Handcoded rules for warping around the sides of the cube.
-            // There are 14 of these, corresponding to the 14 "open" edges in
-            // the flattened cube.
-            //
-            // The mix of (x, y) and (row, col) notation here is because this
-            // part is a direct translation of my Erlang solution where I used
-            // (x, y)-notation.
-            let (x, y, warp_heading) = match (self.pos.col, self.pos.row, self.heading) {
-                (x, y, LEFT) if x == 0 && y <= 149 => (50, 149 - y, RIGHT),
-                (x, y, RIGHT) if x == 149 => (99, 149 - y, LEFT),
-                (x, y, LEFT) if x == 50 && y <= 49 => (0, 149 - y, RIGHT),
-                (x, y, UP) if y == 0 && x <= 99 => (0, x + 100, RIGHT),
-                (x, y, UP) if y == 0 && x >= 100 => (x - 100, 199, UP),
-                (x, y, UP) if y == 100 && x <= 49 => (50, x + 50, RIGHT),
-                (x, y, LEFT) if x == 50 && y >= 50 && y <= 99 => (y - 50, 100, DOWN),
-                (x, y, LEFT) if x == 0 && y >= 150 => (y - 100, 0, DOWN),
-                (x, y, DOWN) if y == 199 => (x + 100, 0, DOWN),
-                (x, y, RIGHT) if x == 49 && y >= 150 => (y - 100, 149, UP),
-                (x, y, DOWN) if y == 149 && x >= 50 => (49, x + 100, LEFT),
-                (x, y, RIGHT) if x == 99 && y >= 50 && y <= 99 => (y + 50, 49, UP),
-                (x, y, DOWN) if x >= 100 && y == 49 => (99, x - 50, LEFT),
-                (x, y, RIGHT) if x == 99 && y >= 100 => (149, 149 - y, LEFT),
-                _ => unreachable!(),
-            };
-            (Pos::new(y, x), warp_heading)
+            input.cube.warp(&self.pos, self.heading)
         } else {
             unreachable!()
         }
     }
 }
 
+type Vec3 = [i32; 3];
+
+fn neg(v: Vec3) -> Vec3 {
+    [-v[0], -v[1], -v[2]]
+}
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(v: Vec3, k: i32) -> Vec3 {
+    [v[0] * k, v[1] * k, v[2] * k]
+}
+
+fn dot(a: Vec3, b: Vec3) -> i32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+// A face of the cube, and how it is oriented in 3D. `right` and `down` are
+// the directions of increasing column and row on the face.
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    row: usize,
+    col: usize,
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Face {
+    // Direction of travel in 3D when moving with the given heading
+    fn direction(&self, heading: Direction) -> Vec3 {
+        match heading {
+            RIGHT => self.right,
+            DOWN => self.down,
+            LEFT => neg(self.right),
+            UP => neg(self.down),
+            _ => unreachable!(),
+        }
+    }
+}
+
+// The map folded into a cube. Works for any of the possible cube nets.
+//
+// Cell centers are placed on the cube in doubled coordinates, so that the
+// cube spans -size..size along each axis and all coordinates are integers.
+#[derive(Debug)]
+pub struct Cube {
+    size: usize,
+    faces: Vec<Face>,
+}
+
+impl Cube {
+    // Returns None if the map is not a cube net
+    fn new(grid: &Grid<char>) -> Option<Cube> {
+        let tiles = grid.iter().filter(|c| **c == '.' || **c == '#').count();
+        let size = ((tiles / 6) as f64).sqrt() as usize;
+        if size == 0 || tiles != 6 * size * size {
+            return None;
+        }
+        let is_face = |row: usize, col: usize| {
+            row < grid.rows() / size
+                && col < grid.cols() / size
+                && matches!(grid[row * size][col * size], '.' | '#')
+        };
+
+        // Fold the net by walking from face to face, starting with the
+        // first face on the top row.
+        let col = (0..grid.cols() / size).find(|col| is_face(0, *col))?;
+        let mut faces = vec![Face {
+            row: 0,
+            col,
+            normal: [0, 0, 1],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        }];
+        let mut i = 0;
+        while i < faces.len() {
+            let f = faces[i];
+            let nbrs = [
+                (f.row, f.col + 1, RIGHT),
+                (f.row + 1, f.col, DOWN),
+                (f.row, f.col.wrapping_sub(1), LEFT),
+                (f.row.wrapping_sub(1), f.col, UP),
+            ];
+            for (row, col, heading) in nbrs {
+                if !is_face(row, col) || faces.iter().any(|g| g.row == row && g.col == col) {
+                    continue;
+                }
+                let normal = f.direction(heading);
+                let (right, down) = match heading {
+                    RIGHT | LEFT => (scale(f.normal, -dot(normal, f.right)), f.down),
+                    _ => (f.right, scale(f.normal, -dot(normal, f.down))),
+                };
+                faces.push(Face {
+                    row,
+                    col,
+                    normal,
+                    right,
+                    down,
+                });
+            }
+            i += 1;
+        }
+        if faces.len() != 6 {
+            return None;
+        }
+
+        Some(Cube { size, faces })
+    }
+
+    fn face_at(&self, pos: &Pos) -> &Face {
+        self.faces
+            .iter()
+            .find(|f| f.row == pos.row / self.size && f.col == pos.col / self.size)
+            .unwrap()
+    }
+
+    // Where we end up when walking over the edge of the face at `pos`
+    fn warp(&self, pos: &Pos, heading: Direction) -> (Pos, Direction) {
+        let n = self.size as i32;
+        let from = self.face_at(pos);
+        let i = (pos.row % self.size) as i32;
+        let j = (pos.col % self.size) as i32;
+        let p = add(
+            scale(from.normal, n),
+            add(
+                scale(from.right, 2 * j + 1 - n),
+                scale(from.down, 2 * i + 1 - n),
+            ),
+        );
+
+        // We continue onto the face in the direction we were walking, and
+        // then walk away from the face we left.
+        let dir = from.direction(heading);
+        let to = self.faces.iter().find(|f| f.normal == dir).unwrap();
+        let p = add(p, add(dir, neg(from.normal)));
+        let new_heading = (0..4)
+            .find(|h| to.direction(*h) == neg(from.normal))
+            .unwrap();
+
+        let i = ((dot(p, to.down) + n - 1) / 2) as usize;
+        let j = ((dot(p, to.right) + n - 1) / 2) as usize;
+        (
+            Pos::new(to.row * self.size + i, to.col * self.size + j),
+            new_heading,
+        )
+    }
+}
+
 // Return the position "forward" from current pos, if the position
 // is on the grid. If the position requires warping, returns None.
 fn get_forward_pos(pos: &Pos, heading: Direction, grid: &Grid<char>) -> Option<(Pos, char)> {
@@ -135,7 +262,7 @@ fn get_forward_pos(pos: &Pos, heading: Direction, grid: &Grid<char>) -> Option<(
     // Since the (row,col) arguments are usize, we need to take care
     // not to try to convert negative numbers to rows and cols.
     match (row, col) {
-        (r, c) if r < 0 || c < 0 || c >= GRID_COLS || r >= GRID_ROWS => None,
+        (r, c) if r < 0 || c < 0 || c >= grid.cols() as i32 || r >= grid.rows() as i32 => None,
         (r, c) => match grid[r as usize][c as usize] {
             tile if tile == '.' || tile == '#' => Some((Pos::new(r as usize, c as usize), tile)),
             _ => None,
@@ -143,41 +270,128 @@ fn get_forward_pos(pos: &Pos, heading: Direction, grid: &Grid<char>) -> Option<(
     }
 }
 
-pub fn solve() -> (i64, i64) {
-    let bytes = include_bytes!("../inputs/input22.txt");
-    let str = String::from_utf8_lossy(bytes);
-    let lines: Vec<String> = str.split("\n").map(|s| s.to_string()).collect();
-    let mut grid: Grid<char> = Grid::new(GRID_ROWS as usize, GRID_COLS as usize);
-    let start_pos = Pos::new(0, str.find('.').unwrap());
+pub struct Input {
+    grid: Grid<char>,
+    cube: Cube,
+    start_pos: Pos,
+    instrs: Vec<Instr>,
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Input;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(bytes: &[u8]) -> ParseResult<Input> {
+        parse(bytes)
+    }
+
+    fn part1(input: &Input) -> i64 {
+        walk(input, 1)
+    }
+
+    fn part2(input: &Input) -> i64 {
+        walk(input, 2)
+    }
+}
+
+// The walk over the map (part 1) or around the cube (part 2) after each
+// instruction. Tiles on the path show the heading we left them with, like in
+// the puzzle text, and the current position is drawn as '@'.
+impl Visualize for Day22 {
+    fn frames(input: Input, parts: Parts) -> Result<Frames, String> {
+        const HEADINGS: [char; 4] = ['>', 'v', '<', '^'];
+        let part = if parts == Parts::Part2 { 2 } else { 1 };
+        let mut state = State::new(&input.start_pos);
+        let mut trail = input.grid.clone();
+
+        Ok(Box::new((0..=input.instrs.len()).map(move |i| {
+            let caption = match i.checked_sub(1).map(|i| &input.instrs[i]) {
+                // One step at a time, to leave a trail. Steps into a wall
+                // are no-ops, so this is the same as walking all at once.
+                Some(Instr::Walk(steps)) => {
+                    for _ in 0..*steps {
+                        trail[state.pos.row][state.pos.col] = HEADINGS[state.heading as usize];
+                        state.walk(&1, &input, part);
+                    }
+                    format!("walk {}", steps)
+                }
+                Some(instr) => {
+                    state.execute(instr, &input, part);
+                    match instr {
+                        Instr::Left => "turn left".to_string(),
+                        _ => "turn right".to_string(),
+                    }
+                }
+                None => "start".to_string(),
+            };
+            trail[state.pos.row][state.pos.col] = HEADINGS[state.heading as usize];
+
+            Frame::draw(
+                caption,
+                trail.cols(),
+                trail.rows(),
+                |col, row| match trail[row][col] {
+                    _ if (row, col) == (state.pos.row, state.pos.col) => '@',
+                    '\0' => ' ',
+                    c => c,
+                },
+            )
+            .with_focus(state.pos.col, state.pos.row)
+        })))
+    }
+}
+
+fn parse(bytes: &[u8]) -> ParseResult<Input> {
+    let str = error::as_str(bytes)?;
+    let (map, path) = str
+        .split_once("\n\n")
+        .ok_or_else(|| ParseError::eof(str, "expected an empty line after the map"))?;
+    let lines: Vec<&str> = map.split("\n").collect();
+    let cols = lines.iter().map(|line| line.len()).max().unwrap();
+    let mut grid: Grid<char> = Grid::new(lines.len(), cols);
+    let start_pos = Pos::new(
+        0,
+        lines[0]
+            .find('.')
+            .ok_or_else(|| ParseError::at(str, lines[0], "no open tile on the first row"))?,
+    );
 
     for (row, line) in lines.iter().enumerate() {
-        if row >= GRID_ROWS as usize {
-            // remaining rows are part of the instruction block
-            break;
-        }
+        error::check_chars(str, line, |c| " .#".contains(c))?;
         for (col, c) in line.chars().enumerate() {
             grid[row][col] = c
         }
     }
 
-    let instrs: Vec<Instr> = regex!(r"(\d+|[RL])")
-        .find_iter(&str)
+    let path = path.trim_end();
+    error::check_chars(str, path, |c| c.is_ascii_digit() || c == 'L' || c == 'R')?;
+    let instrs = regex!(r"(\d+|[RL])")
+        .find_iter(path)
         .map(|m| match m.as_str() {
-            "L" => Instr::Left,
-            "R" => Instr::Right,
-            walk => Instr::Walk(walk.parse().unwrap()),
+            "L" => Ok(Instr::Left),
+            "R" => Ok(Instr::Right),
+            walk => Ok(Instr::Walk(error::number(str, walk)?)),
         })
-        .collect();
+        .collect::<ParseResult<Vec<Instr>>>()?;
+
+    let cube = Cube::new(&grid)
+        .ok_or_else(|| ParseError::at(str, map, "the map does not fold into a cube"))?;
 
-    let p1 = walk(&start_pos, &instrs, &grid, 1);
-    let p2 = walk(&start_pos, &instrs, &grid, 2);
-    (p1, p2)
+    Ok(Input {
+        cube,
+        grid,
+        start_pos,
+        instrs,
+    })
 }
 
-fn walk(start_pos: &Pos, instrs: &Vec<Instr>, grid: &Grid<char>, part: i32) -> i64 {
-    let mut state = State::new(start_pos);
-    for instr in instrs {
-        state.execute(instr, grid, part)
+fn walk(input: &Input, part: i32) -> i64 {
+    let mut state = State::new(&input.start_pos);
+    for instr in &input.instrs {
+        state.execute(instr, input, part)
     }
     to_password(&state)
 }
@@ -1,7 +1,10 @@
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::{Parts, Solution};
+use crate::visualize::{Frame, Frames, Visualize};
 use hashbrown::HashMap;
 use rayon::iter::ParallelIterator;
 
-type Pos = (i32, i32);
+pub type Pos = (i32, i32);
 
 const NORTH: i32 = 0;
 const SOUTH: i32 = 1;
@@ -106,34 +109,83 @@ fn find_p1(elves: &HashMap<Pos, ()>) -> i32 {
     (max_x - min_x + 1) * (max_y - min_y + 1) - elves.len() as i32
 }
 
-pub fn solve() -> (i32, i32) {
-    let bytes = include_bytes!("../inputs/input23.txt");
-    let mut elves: HashMap<Pos, ()> = HashMap::new();
+pub type Elves = HashMap<Pos, ()>;
 
-    String::from_utf8_lossy(bytes)
-        .split("\n")
-        .enumerate()
-        .for_each(|(y, line)| {
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Elves;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(bytes: &[u8]) -> ParseResult<Elves> {
+        let mut elves: Elves = HashMap::new();
+        let input = error::as_str(bytes)?;
+
+        for (y, line) in input.split("\n").enumerate() {
+            error::check_chars(input, line, |c| c == '#' || c == '.')?;
             line.chars().enumerate().for_each(|(x, c)| {
                 if c == '#' {
                     elves.insert((x as i32, y as i32), ());
                 }
             });
-        });
+        }
+        // There is no rectangle around no elves
+        if elves.is_empty() {
+            return Err(ParseError::eof(input, "no elves"));
+        }
 
-    let mut p1 = 0;
-    let mut p2 = 0;
+        Ok(elves)
+    }
 
-    for round in 0.. {
-        if round == 10 {
-            p1 = find_p1(&elves);
+    fn part1(elves: &Elves) -> i32 {
+        let mut elves = elves.clone();
+        for round in 0..10 {
+            do_one_round(&mut elves, round);
         }
+        find_p1(&elves)
+    }
 
-        if !do_one_round(&mut elves, round) {
-            p2 = round + 1;
-            break;
+    fn part2(elves: &Elves) -> i32 {
+        let mut elves = elves.clone();
+        let mut round = 0;
+        while do_one_round(&mut elves, round) {
+            round += 1;
         }
+        round + 1
     }
+}
+
+// The elves after each round, for the 10 rounds of part 1 or until they stop
+// moving (part 2)
+impl Visualize for Day23 {
+    fn frames(mut elves: Elves, parts: Parts) -> Result<Frames, String> {
+        let rounds = if parts == Parts::Part2 { i32::MAX } else { 10 };
+        let mut done = false;
 
-    (p1, p2)
+        Ok(Box::new((0..=rounds).map_while(move |round| {
+            if done {
+                return None;
+            }
+            if round > 0 {
+                done = !do_one_round(&mut elves, round - 1);
+            }
+            let min_x = elves.keys().map(|(x, _)| *x).min().unwrap_or(0);
+            let max_x = elves.keys().map(|(x, _)| *x).max().unwrap_or(0);
+            let min_y = elves.keys().map(|(_, y)| *y).min().unwrap_or(0);
+            let max_y = elves.keys().map(|(_, y)| *y).max().unwrap_or(0);
+            Some(Frame::draw(
+                format!("round {}", round),
+                (max_x - min_x + 1) as usize,
+                (max_y - min_y + 1) as usize,
+                |col, row| {
+                    if elves.contains_key(&(min_x + col as i32, min_y + row as i32)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                },
+            ))
+        })))
+    }
 }
@@ -1,16 +1,19 @@
-use std::{collections::BTreeSet, fmt};
+use crate::error::{self, ParseError, ParseResult};
+use crate::solution::{Parts, Solution};
+use crate::visualize::{Frame, Frames, Visualize};
+use std::{cell::OnceCell, collections::BTreeSet, fmt};
 
 use hashbrown::{HashMap, HashSet};
 use lazy_regex::regex;
 use regex::Match;
 
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Ord, PartialOrd)]
-struct Coord {
+pub struct Coord {
     x: i32,
     y: i32,
 }
-type GridMap = HashMap<Coord, char>;
-type CoordSet = HashSet<Coord>;
+pub type GridMap = HashMap<Coord, char>;
+pub type CoordSet = HashSet<Coord>;
 
 impl fmt::Display for Coord {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -18,11 +21,12 @@ impl fmt::Display for Coord {
     }
 }
 
-// A* search node
+// A* search node, with where it is in the trail of positions reached
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 struct Node {
     fscore: i32,
     pos: Pos,
+    trail: usize,
 }
 
 impl fmt::Display for Node {
@@ -56,88 +60,231 @@ fn match_to_coord(m: &Match, width: i32) -> Coord {
     Coord { x, y }
 }
 
-pub fn solve() -> (i32, i32) {
-    let bytes = include_bytes!("../inputs/input24.txt");
-    let input = String::from_utf8_lossy(bytes);
-    let w = (input.find('\n').unwrap() + 1) as i32 - 3;
-    let h = regex!(r"\n").find_iter(&input).count() as i32 - 2;
-    let mut cache: HashMap<i32, CoordSet> = HashMap::new();
+pub struct Valley {
+    blizzards: GridMap,
+    walls: CoordSet,
+    w: i32,
+    h: i32,
+    start: Coord,
+    end: Coord,
+    // The route of the first trip, which part 2 continues from
+    first_trip: OnceCell<Route>,
+}
 
-    let blizzards: GridMap = regex!(r"([<>v^])")
-        .find_iter(&input)
-        .map(|m| match_to_pair(&m, w))
-        .collect::<HashMap<Coord, char>>();
+// The position at each minute, or why there is no way
+type Route = Result<Vec<Coord>, String>;
+
+impl Valley {
+    // The route of `n` trips, back and forth, from minute 0
+    fn trips(&self, n: usize) -> Route {
+        let first = self
+            .first_trip
+            .get_or_init(|| search(self, self.start, self.end, 0, &mut HashMap::new()));
+        let mut route = first.clone()?;
+        let mut cache: HashMap<i32, CoordSet> = HashMap::new();
+        for (from, to) in [(self.end, self.start), (self.start, self.end)]
+            .into_iter()
+            .take(n - 1)
+        {
+            let leg = search(self, from, to, route.len() as i32 - 1, &mut cache)?;
+            route.extend(leg.into_iter().skip(1));
+        }
+        Ok(route)
+    }
+}
+
+// An end which cannot be reached fails the part, with the same message as
+// when visualizing it
+fn minutes(route: Route) -> i32 {
+    match route {
+        Ok(route) => route.len() as i32 - 1,
+        Err(err) => panic!("{}", err),
+    }
+}
 
-    let walls = regex!(r"#")
-        .find_iter(&input)
-        .map(|m| match_to_coord(&m, w))
-        .collect::<CoordSet>();
+pub struct Day24;
 
-    let start = Coord { x: 1, y: 0 };
-    let end = Coord { x: w, y: h + 1 };
+impl Solution for Day24 {
+    type Input = Valley;
+    type Part1 = i32;
+    type Part2 = i32;
 
-    let p1 = search(start, end, &blizzards, &mut cache, &walls, w, h, 0);
-    let p2a = search(end, start, &blizzards, &mut cache, &walls, w, h, p1);
-    let p2b = search(start, end, &blizzards, &mut cache, &walls, w, h, p2a);
-    (p1, p2b)
+    fn parse(bytes: &[u8]) -> ParseResult<Valley> {
+        let input = error::as_str(bytes)?;
+        let rows: Vec<&str> = input.lines().collect();
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.len() < 3 || width < 3 {
+            return Err(ParseError::eof(
+                input,
+                "expected at least 3 rows and columns",
+            ));
+        }
+        for line in &rows {
+            error::check_chars(input, line, |c| "#.<>^v".contains(c))?;
+            if line.len() != width {
+                return Err(ParseError::at(
+                    input,
+                    line,
+                    "rows must have the same length",
+                ));
+            }
+        }
+        // Walls all around, with a gap at the top and one at the bottom
+        for line in &rows[1..rows.len() - 1] {
+            if !line.starts_with('#') || !line.ends_with('#') {
+                return Err(ParseError::at(input, line, "expected walls at both ends"));
+            }
+        }
+        let gap = |line: &str| match line.match_indices('.').collect::<Vec<_>>()[..] {
+            [(x, _)] if line.chars().all(|c| c == '#' || c == '.') => Ok(x as i32),
+            _ => Err(ParseError::at(input, line, "expected a wall with one gap")),
+        };
+        let start = gap(rows[0])?;
+        let end = gap(rows[rows.len() - 1])?;
+
+        let w = width as i32 - 2;
+        let h = rows.len() as i32 - 2;
+
+        let blizzards: GridMap = regex!(r"([<>v^])")
+            .find_iter(input)
+            .map(|m| match_to_pair(&m, w))
+            .collect::<HashMap<Coord, char>>();
+
+        let walls = regex!(r"#")
+            .find_iter(input)
+            .map(|m| match_to_coord(&m, w))
+            .collect::<CoordSet>();
+
+        Ok(Valley {
+            blizzards,
+            walls,
+            w,
+            h,
+            start: Coord { x: start, y: 0 },
+            end: Coord { x: end, y: h + 1 },
+            first_trip: OnceCell::new(),
+        })
+    }
+
+    fn part1(v: &Valley) -> i32 {
+        minutes(v.trips(1))
+    }
+
+    fn part2(v: &Valley) -> i32 {
+        minutes(v.trips(3))
+    }
+}
+
+// The valley at each minute of the trip (part 1) or the three trips (part
+// 2), with the expedition drawn as 'E'
+impl Visualize for Day24 {
+    fn frames(v: Valley, parts: Parts) -> Result<Frames, String> {
+        let path = v.trips(if parts == Parts::Part2 { 3 } else { 1 })?;
+
+        Ok(Box::new(path.into_iter().enumerate().map(
+            move |(minute, pos)| {
+                let blizzards = blizzards_at(&v, minute as i32);
+                Frame::draw(
+                    format!("minute {}", minute),
+                    (v.w + 2) as usize,
+                    (v.h + 2) as usize,
+                    |col, row| {
+                        let coord = Coord {
+                            x: col as i32,
+                            y: row as i32,
+                        };
+                        match blizzards.get(&coord) {
+                            _ if coord == pos => 'E',
+                            _ if v.walls.contains(&coord) => '#',
+                            Some((dir, 1)) => *dir,
+                            Some((_, n)) => char::from_digit(*n as u32, 10).unwrap_or('*'),
+                            None => '.',
+                        }
+                    },
+                )
+                .with_focus(pos.x as usize, pos.y as usize)
+            },
+        )))
+    }
+}
+
+// The blizzards at the given minute, with their direction and how many share
+// each position
+fn blizzards_at(v: &Valley, time: i32) -> HashMap<Coord, (char, usize)> {
+    let mut blizzards = HashMap::new();
+    for (coord, dir) in &v.blizzards {
+        let pos = blizzard_pos(coord, time, *dir, v.w, v.h);
+        blizzards.entry(pos).or_insert((*dir, 0)).1 += 1;
+    }
+    blizzards
 }
 
+// The fastest route from `start` to `end` leaving at minute `t0`, as the
+// position at each minute. The blizzards are back where they started every
+// w * h minutes, so there is no point in being somewhere at the same minute
+// of that cycle as before, unless it is earlier. When there is nothing left
+// to visit, `end` cannot be reached.
 fn search(
+    v: &Valley,
     start: Coord,
     end: Coord,
-    orig_blizzards: &GridMap,
-    blizzard_cache: &mut HashMap<i32, CoordSet>,
-    walls: &CoordSet,
-    width: i32,
-    height: i32,
     t0: i32,
-) -> i32 {
+    blizzard_cache: &mut HashMap<i32, CoordSet>,
+) -> Route {
+    let period = v.w * v.h;
     let mut open_set: BTreeSet<Node> = BTreeSet::new();
-    let mut gscore: HashMap<Pos, i32> = HashMap::new();
-    let start_pos = Pos {
-        coord: start,
-        time: t0,
-    };
+    // The earliest minute at each position and minute of the cycle
+    let mut earliest: HashMap<(Coord, i32), i32> = HashMap::new();
+    // Each position reached, and the index of the one it was reached from
+    let mut trail: Vec<(Coord, usize)> = vec![(start, 0)];
 
-    gscore.insert(start_pos.clone(), 0);
+    earliest.insert((start, t0 % period), t0);
     open_set.insert(Node {
         fscore: dist(&start, &end),
-        pos: start_pos.clone(),
+        pos: Pos {
+            coord: start,
+            time: t0,
+        },
+        trail: 0,
     });
 
-    loop {
-        let node = open_set.pop_first().unwrap(); // empty means search exhausted and failed
-
+    while let Some(node) = open_set.pop_first() {
         if node.pos.coord == end {
-            return node.pos.time - 1;
-        } else {
-            // Get neighbors of node, and cache any additional computed blizzard states
-            let blizzards = blizzard_cache.entry(node.pos.time).or_insert_with(|| {
-                orig_blizzards
-                    .iter()
-                    .map(|(coord, dir)| blizzard_pos(&coord, node.pos.time, *dir, width, height))
-                    .collect::<CoordSet>()
-            });
-
-            let neighbors = all_neighbors(&node, width, height, &blizzards, &walls);
-            for nbr in neighbors {
-                // println!("Neighbor: {}", nbr);
-                let tentative_gscore: i32 = *gscore.get(&node.pos).unwrap() + 1;
-                let gscore_nbr: i32 = *gscore.get(&nbr).unwrap_or(&i32::MAX);
-
-                if tentative_gscore < gscore_nbr {
-                    // We have reached this position the first time, or through
-                    // a shorter path than before
-                    let nbr_node = Node {
-                        fscore: tentative_gscore + dist(&nbr.coord, &end),
-                        pos: nbr.clone(),
-                    };
-                    open_set.insert(nbr_node);
-                    gscore.insert(nbr.clone(), tentative_gscore);
-                }
+            let mut route = vec![end];
+            let mut i = node.trail;
+            while i > 0 {
+                i = trail[i].1;
+                route.push(trail[i].0);
+            }
+            route.reverse();
+            return Ok(route);
+        }
+
+        // Get neighbors of node, and cache any additional computed blizzard states
+        let time = node.pos.time + 1;
+        let blizzards = blizzard_cache.entry(time % period).or_insert_with(|| {
+            v.blizzards
+                .iter()
+                .map(|(coord, dir)| blizzard_pos(coord, time, *dir, v.w, v.h))
+                .collect::<CoordSet>()
+        });
+
+        for nbr in all_neighbors(&node, v.w, v.h, blizzards, &v.walls) {
+            let time = earliest
+                .entry((nbr.coord, nbr.time % period))
+                .or_insert(i32::MAX);
+            if nbr.time < *time {
+                *time = nbr.time;
+                trail.push((nbr.coord, node.trail));
+                open_set.insert(Node {
+                    fscore: nbr.time - t0 + dist(&nbr.coord, &end),
+                    pos: nbr,
+                    trail: trail.len() - 1,
+                });
             }
         }
     }
+    Err(format!("there is no way from {} to {}", start, end))
 }
 
 fn dist(a: &Coord, b: &Coord) -> i32 {
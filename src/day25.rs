@@ -1,12 +1,35 @@
-pub fn solve() -> String {
-    rev_snafu(
-        String::from_utf8_lossy(include_bytes!("../inputs/input25.txt"))
+use crate::error::{self, ParseResult};
+use crate::solution::Solution;
+
+pub struct Day25;
+
+// There is no second part on the last day, so part 2 is always empty
+impl Solution for Day25 {
+    type Input = Vec<String>;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(buf: &[u8]) -> ParseResult<Vec<String>> {
+        let input = error::as_str(buf)?;
+        input
             .split("\n")
-            .fold(0i64, |sum, line| snafu(line) + sum),
-    )
+            .map(|s| {
+                error::check_chars(input, s, |c| "=-012".contains(c))?;
+                Ok(s.to_string())
+            })
+            .collect()
+    }
+
+    fn part1(lines: &Vec<String>) -> String {
+        rev_snafu(lines.iter().fold(0i64, |sum, line| snafu(line) + sum))
+    }
+
+    fn part2(_lines: &Vec<String>) -> String {
+        String::new()
+    }
 }
 
-fn snafu(s: &str) -> i64 {
+pub fn snafu(s: &str) -> i64 {
     let (_, sum) = s.chars().rev().fold((1i64, 0), |(n, sum), c| {
         let m = match c {
             '2' => sum + 2 * n,
@@ -21,7 +44,7 @@ fn snafu(s: &str) -> i64 {
     sum
 }
 
-fn rev_snafu(n: i64) -> String {
+pub fn rev_snafu(n: i64) -> String {
     let mut s: Vec<char> = Vec::new();
     let mut n0 = n;
     loop {
@@ -0,0 +1,144 @@
+use crate::registry;
+use std::fmt;
+use std::str::FromStr;
+
+// Error from parsing a puzzle input, pointing at the offending text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub year: u16, // 0 if not known yet
+    pub day: u8,   // 0 if not known yet
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+    source_line: String,
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+impl ParseError {
+    // `text` must be a slice of `input`, which is used to find the line and
+    // column. An empty slice at the end of `input` means that the input was
+    // truncated.
+    pub fn at(input: &str, text: &str, message: &str) -> ParseError {
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| *offset <= input.len())
+            .expect("text is not part of the input");
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        ParseError {
+            year: 0,
+            day: 0,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: text.to_string(),
+            message: message.to_string(),
+            source_line: input[line_start..line_end].to_string(),
+        }
+    }
+
+    pub fn eof(input: &str, message: &str) -> ParseError {
+        ParseError::at(input, &input[input.len()..], message)
+    }
+
+    pub fn in_day(mut self, year: u16, day: u8) -> ParseError {
+        self.year = year;
+        self.day = day;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.day > 0 {
+            write!(f, "day {}, ", registry::name(self.year, self.day))?;
+        }
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if !self.text.is_empty() {
+            write!(f, ": {:?}", self.text)?;
+        }
+
+        // Show the line with the offending text underlined
+        let number = self.line.to_string();
+        let marker = "^".repeat(self.text.chars().count().max(1));
+        write!(
+            f,
+            "\n{} | {}\n{} | {}{}",
+            number,
+            self.source_line,
+            " ".repeat(number.len()),
+            " ".repeat(self.column - 1),
+            marker
+        )
+    }
+}
+
+// Inputs must be valid UTF-8
+pub fn as_str(buf: &[u8]) -> ParseResult<&str> {
+    std::str::from_utf8(buf).map_err(|err| {
+        let valid = std::str::from_utf8(&buf[..err.valid_up_to()]).unwrap();
+        ParseError::eof(valid, "invalid UTF-8")
+    })
+}
+
+pub fn number<T: FromStr>(input: &str, text: &str) -> ParseResult<T> {
+    text.parse()
+        .map_err(|_| ParseError::at(input, text, "expected a number"))
+}
+
+// Like `number`, for a token on `line` which may be missing
+pub fn next_number<T: FromStr>(input: &str, line: &str, token: Option<&str>) -> ParseResult<T> {
+    match token {
+        Some(text) => number(input, text),
+        None => Err(ParseError::at(
+            input,
+            &line[line.len()..],
+            "expected a number",
+        )),
+    }
+}
+
+// Fails on the first character on `line` which is not valid
+pub fn check_chars(input: &str, line: &str, valid: impl Fn(char) -> bool) -> ParseResult<()> {
+    match line.char_indices().find(|(_, c)| !valid(*c)) {
+        Some((i, c)) => Err(ParseError::at(
+            input,
+            &line[i..i + c.len_utf8()],
+            "unexpected character",
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location() {
+        let input = "1,2\n3,x4\n";
+        let err = number::<i32>(input, &input[6..8]).unwrap_err();
+        assert_eq!((2, 3), (err.line, err.column));
+        assert_eq!("x4", err.text);
+        assert_eq!(
+            "day 04, line 2, column 3: expected a number: \"x4\"\n2 | 3,x4\n  |   ^^",
+            err.clone().in_day(2022, 4).to_string()
+        );
+        assert!(err
+            .in_day(2023, 4)
+            .to_string()
+            .starts_with("day 2023-04, line 2"));
+
+        let err = ParseError::eof(input, "truncated");
+        assert_eq!((3, 1), (err.line, err.column));
+    }
+}
@@ -0,0 +1,872 @@
+// Random puzzle inputs, for stress tests and scaling studies. The inputs
+// follow the format of the real ones, and are built so that the puzzles
+// have an answer.
+//
+// What `size` means depends on the day, see `SIZES`.
+
+use crate::ocr;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::Path;
+
+// Default size of the generated input (close to the real inputs), and what
+// the size measures
+pub const SIZES: [(usize, &str); 25] = [
+    (250, "elves"),
+    (2500, "rounds"),
+    (300, "rucksacks"),
+    (1000, "pairs"),
+    (500, "moves"),
+    (4096, "characters"),
+    (500, "files and directories"),
+    (99, "grid side"),
+    (2000, "moves"),
+    (8, "letters (ignored, the screen always has 8)"),
+    (8, "monkeys"),
+    (160, "grid width"),
+    (150, "pairs"),
+    (150, "rock paths"),
+    (30, "sensors"),
+    (55, "valves (at most 62)"),
+    (10091, "jets"),
+    (2800, "cubes"),
+    (30, "blueprints"),
+    (5000, "numbers"),
+    (2000, "monkeys"),
+    (50, "cube side"),
+    (70, "grid side"),
+    (120, "valley width"),
+    (100, "numbers"),
+];
+
+// Small and fast, with a reproducible sequence for each seed (splitmix64)
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in lo..=hi
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as i64
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64) < p * (1u64 << 53) as f64
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    fn letters(&mut self, alphabet: &[u8], n: usize) -> String {
+        (0..n).map(|_| self.pick(alphabet) as char).collect()
+    }
+}
+
+const LOWER: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPER: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// The year the generators are for
+pub const YEAR: u16 = 2022;
+
+// Returns None if there is no such day
+pub fn generate(day: u8, size: Option<usize>, seed: u64) -> Option<String> {
+    let size = size.unwrap_or(SIZES.get((day as usize).checked_sub(1)?)?.0);
+    let rng = &mut Rng::new(seed);
+    let text = match day {
+        1 => day01(rng, size),
+        2 => day02(rng, size),
+        3 => day03(rng, size),
+        4 => day04(rng, size),
+        5 => day05(rng, size),
+        6 => day06(rng, size),
+        7 => day07(rng, size),
+        8 => day08(rng, size),
+        9 => day09(rng, size),
+        10 => day10(rng),
+        11 => day11(rng, size),
+        12 => day12(rng, size),
+        13 => day13(rng, size),
+        14 => day14(rng, size),
+        15 => day15(rng, size),
+        16 => day16(rng, size),
+        17 => day17(rng, size),
+        18 => day18(rng, size),
+        19 => day19(rng, size),
+        20 => day20(rng, size),
+        21 => day21(rng, size),
+        22 => day22(rng, size),
+        23 => day23(rng, size),
+        24 => day24(rng, size),
+        25 => day25(rng, size),
+        _ => return None,
+    };
+    Some(text)
+}
+
+// Writes a generated input to `output` (stdout by default)
+pub fn write_input(
+    day: u8,
+    size: Option<usize>,
+    seed: u64,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let input = generate(day, size, seed)
+        .ok_or_else(|| format!("There is no generator for day {}", day))?;
+    let (_, unit) = SIZES[day as usize - 1];
+    match output {
+        None => print!("{}", input),
+        Some(path) => {
+            std::fs::write(path, input)
+                .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+            eprintln!("Wrote day {} input ({}) to {}", day, unit, path.display());
+        }
+    }
+    Ok(())
+}
+
+fn lines(items: impl Iterator<Item = String>) -> String {
+    items.map(|line| line + "\n").collect()
+}
+
+fn day01(rng: &mut Rng, size: usize) -> String {
+    let elves: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            let n = rng.range(1, 15);
+            lines((0..n).map(|_| rng.range(1000, 60000).to_string()))
+        })
+        .collect();
+    elves.join("\n")
+}
+
+fn day02(rng: &mut Rng, size: usize) -> String {
+    lines((0..size).map(|_| format!("{} {}", rng.pick(b"ABC") as char, rng.pick(b"XYZ") as char)))
+}
+
+// Each rucksack has exactly one item in both compartments, and each group
+// of three has exactly one item in common. Letters are split into disjoint
+// pools so that nothing else can be shared by accident.
+fn day03(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size.div_ceil(3).max(1) {
+        let mut letters: Vec<u8> = [LOWER, UPPER].concat();
+        rng.shuffle(&mut letters);
+        let badge = letters[0] as char;
+        for pool in letters[1..].chunks(17) {
+            let (shared, rest) = (pool[0] as char, &pool[1..]);
+            let (left, right) = rest.split_at(rest.len() / 2);
+            let n = rng.below(12);
+            let mut first: Vec<char> = vec![badge, shared];
+            first.extend(rng.letters(left, n).chars());
+            let mut second: Vec<char> = vec![shared];
+            second.extend(rng.letters(right, n + 1).chars());
+            rng.shuffle(&mut first);
+            rng.shuffle(&mut second);
+            out.extend(first.into_iter().chain(second));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn day04(rng: &mut Rng, size: usize) -> String {
+    let section = |rng: &mut Rng| {
+        let a = rng.range(1, 99);
+        let b = rng.range(a, 99);
+        format!("{}-{}", a, b)
+    };
+    lines((0..size.max(1)).map(|_| format!("{},{}", section(rng), section(rng))))
+}
+
+fn day05(rng: &mut Rng, size: usize) -> String {
+    // Bottom of each stack first
+    let mut stacks: Vec<Vec<char>> = (0..9)
+        .map(|_| {
+            let n = rng.below(8) + 1;
+            rng.letters(UPPER, n).chars().collect()
+        })
+        .collect();
+
+    let mut out = String::new();
+    let height = stacks.iter().map(|s| s.len()).max().unwrap();
+    for level in (0..height).rev() {
+        let row: Vec<String> = stacks
+            .iter()
+            .map(|s| match s.get(level) {
+                Some(c) => format!("[{}]", c),
+                None => "   ".to_string(),
+            })
+            .collect();
+        writeln!(out, "{}", row.join(" ")).unwrap();
+    }
+    let labels: Vec<String> = (1..=9).map(|i| format!(" {} ", i)).collect();
+    writeln!(out, "{}\n", labels.join(" ")).unwrap();
+
+    for _ in 0..size.max(1) {
+        let from = loop {
+            let i = rng.below(9);
+            if !stacks[i].is_empty() {
+                break i;
+            }
+        };
+        let to = (from + 1 + rng.below(8)) % 9;
+        let n = rng.below(stacks[from].len().min(10)) + 1;
+        let at = stacks[from].len() - n;
+        let moved: Vec<char> = stacks[from].drain(at..).collect();
+        stacks[to].extend(moved);
+        writeln!(out, "move {} from {} to {}", n, from + 1, to + 1).unwrap();
+    }
+    out
+}
+
+// There is always a start-of-message marker near the end
+fn day06(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(15);
+    let mut chars: Vec<u8> = (0..size).map(|_| rng.pick(LOWER)).collect();
+    let mut marker = LOWER.to_vec();
+    rng.shuffle(&mut marker);
+    chars[size - 15..size - 1].copy_from_slice(&marker[..14]);
+    String::from_utf8(chars).unwrap() + "\n"
+}
+
+fn day07(rng: &mut Rng, size: usize) -> String {
+    // Each directory has a name, subdirectories and files
+    struct Dir {
+        name: String,
+        dirs: Vec<usize>,
+        files: Vec<(i64, String)>,
+    }
+    let mut dirs = vec![Dir {
+        name: "/".to_string(),
+        dirs: vec![],
+        files: vec![],
+    }];
+    for i in 0..size {
+        let parent = rng.below(dirs.len());
+        // Names are made unique by a numeric suffix
+        let n = rng.range(1, 8) as usize;
+        let name = format!("{}{}", rng.letters(LOWER, n), i);
+        if rng.chance(0.3) {
+            // The solution expects every directory to hold at least one file
+            let child = dirs.len();
+            dirs[parent].dirs.push(child);
+            dirs.push(Dir {
+                name: name.clone(),
+                dirs: vec![],
+                files: vec![(rng.range(1000, 300000), name + ".dat")],
+            });
+        } else {
+            let ext = if rng.chance(0.5) { ".txt" } else { "" };
+            let file = (rng.range(1000, 300000), name + ext);
+            dirs[parent].files.push(file);
+        }
+    }
+
+    fn walk(dirs: &[Dir], i: usize, out: &mut String) {
+        let dir = &dirs[i];
+        writeln!(out, "$ cd {}\n$ ls", dir.name).unwrap();
+        for d in &dir.dirs {
+            writeln!(out, "dir {}", dirs[*d].name).unwrap();
+        }
+        for (size, name) in &dir.files {
+            writeln!(out, "{} {}", size, name).unwrap();
+        }
+        for d in &dir.dirs {
+            walk(dirs, *d, out);
+            writeln!(out, "$ cd ..").unwrap();
+        }
+    }
+    let mut out = String::new();
+    walk(&dirs, 0, &mut out);
+    out
+}
+
+fn day08(rng: &mut Rng, size: usize) -> String {
+    lines((0..size.max(1)).map(|_| rng.letters(b"0123456789", size.max(1))))
+}
+
+fn day09(rng: &mut Rng, size: usize) -> String {
+    lines((0..size.max(1)).map(|_| format!("{} {}", rng.pick(b"UDLR") as char, rng.range(1, 20))))
+}
+
+// A program which draws 8 random letters in exactly 240 cycles, like the
+// real inputs. X has to keep its value for at least two cycles (an addx),
+// so the screen is split into runs of 2 or 3 cycles, each with a value of X
+// which draws all of its pixels right.
+fn day10(rng: &mut Rng) -> String {
+    const CYCLES: usize = 240;
+    let lit = |x: i64, cycle: usize| (x - (cycle % 40) as i64).abs() <= 1;
+    loop {
+        let text: String = (0..8)
+            .map(|_| rng.pick(ocr::LETTERS.as_bytes()) as char)
+            .collect();
+        let screen: Vec<bool> = ocr::draw(&text)
+            .unwrap()
+            .chars()
+            .map(|c| c == '#')
+            .collect();
+        // The values of X which draw the given cycles right
+        let xs = |cycles: std::ops::Range<usize>| -> Vec<i64> {
+            (-1..=40)
+                .filter(|x| cycles.clone().all(|c| lit(*x, c) == screen[c]))
+                .collect()
+        };
+
+        // Whether the screen from each cycle on can be drawn
+        let mut drawable = vec![false; CYCLES + 1];
+        drawable[CYCLES] = true;
+        for start in (0..CYCLES - 1).rev() {
+            drawable[start] = [2, 3].iter().any(|len| {
+                start + len <= CYCLES && drawable[start + len] && !xs(start..start + len).is_empty()
+            });
+        }
+
+        // X starts at 1
+        let mut runs: Vec<(usize, i64)> = vec![];
+        let mut start = 0;
+        while start < CYCLES {
+            let options: Vec<(usize, i64)> = [2, 3]
+                .iter()
+                .filter(|len| start + *len <= CYCLES && drawable[start + *len])
+                .flat_map(|len| xs(start..start + len).into_iter().map(|x| (*len, x)))
+                .filter(|(_, x)| start > 0 || *x == 1)
+                .collect();
+            if options.is_empty() {
+                break;
+            }
+            let (len, x) = rng.pick(&options);
+            runs.push((len, x));
+            start += len;
+        }
+        if start < CYCLES {
+            continue;
+        }
+
+        let mut out = String::new();
+        for (i, (len, x)) in runs.iter().enumerate() {
+            let next = runs.get(i + 1).map_or(*x, |(_, next)| *next);
+            out.push_str(&"noop\n".repeat(len - 2));
+            writeln!(out, "addx {}", next - x).unwrap();
+        }
+        return out;
+    }
+}
+
+fn day11(rng: &mut Rng, size: usize) -> String {
+    const PRIMES: [i64; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+    let n = size.max(2);
+    let mut monkeys = vec![];
+    for i in 0..n {
+        let count = rng.below(5) + 1;
+        let items: Vec<String> = (0..count).map(|_| rng.range(50, 99).to_string()).collect();
+        // Worry levels are divided by 3 after each inspection in part 1, so
+        // multiplying by more than 3 (or squaring) could make them overflow
+        // when an item is passed around many times
+        let op = match rng.below(3) {
+            0 => format!("old * {}", rng.range(2, 3)),
+            _ => format!("old + {}", rng.range(1, 8)),
+        };
+        let on_true = (i + 1 + rng.below(n - 1)) % n;
+        let on_false = loop {
+            let j = rng.below(n);
+            if j != i && (j != on_true || n == 2) {
+                break j;
+            }
+        };
+        monkeys.push(format!(
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  Test: divisible by {}\n    \
+             If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+            i,
+            items.join(", "),
+            op,
+            PRIMES[i % PRIMES.len()],
+            on_true,
+            on_false
+        ));
+    }
+    monkeys.join("\n")
+}
+
+// The elevation rises from left to right. Cells off the path from S to E
+// are lowered at random, which makes dead ends but never blocks the path.
+fn day12(rng: &mut Rng, size: usize) -> String {
+    let width = size.max(26);
+    let height = (width / 4).max(5);
+    let path_row = rng.below(height);
+    let end_row = rng.below(height);
+    let mut grid: Vec<Vec<u8>> = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let level = (x * 26 / width) as i64;
+                    let dip = if y == path_row { 0 } else { rng.range(0, 3) };
+                    b'a' + (level - dip).max(0) as u8
+                })
+                .collect()
+        })
+        .collect();
+    // The last column is all 'z', so E can be reached from the path row
+    grid[path_row][0] = b'S';
+    for row in grid.iter_mut() {
+        row[width - 1] = b'z';
+    }
+    grid[end_row][width - 1] = b'E';
+    lines(grid.into_iter().map(|row| String::from_utf8(row).unwrap()))
+}
+
+fn packet(rng: &mut Rng, depth: usize) -> String {
+    let n = rng.below(5);
+    let items: Vec<String> = (0..n)
+        .map(|_| {
+            if depth < 3 && rng.chance(0.3) {
+                packet(rng, depth + 1)
+            } else {
+                rng.range(0, 10).to_string()
+            }
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+fn day13(rng: &mut Rng, size: usize) -> String {
+    let pairs: Vec<String> = (0..size.max(1))
+        .map(|_| format!("{}\n{}\n", packet(rng, 0), packet(rng, 0)))
+        .collect();
+    pairs.join("\n")
+}
+
+// Rock paths below the sand source, well within the cave
+fn day14(rng: &mut Rng, size: usize) -> String {
+    lines((0..size.max(1)).map(|_| {
+        let (mut x, mut y) = (rng.range(420, 580), rng.range(10, 150));
+        let mut points = vec![format!("{},{}", x, y)];
+        for i in 0..rng.range(1, 5) {
+            if i % 2 == 0 {
+                x = (x + rng.range(-8, 8)).clamp(400, 600);
+            } else {
+                y = (y + rng.range(-8, 8)).clamp(1, 160);
+            }
+            points.push(format!("{},{}", x, y));
+        }
+        points.join(" -> ")
+    }))
+}
+
+// Four sensors have the distress beacon just outside their range, one on
+// each diagonal. The other sensors are placed at random, but do not reach
+// it either.
+fn day15(rng: &mut Rng, size: usize) -> String {
+    const MAX: i64 = 4000000;
+    let dist = |a: (i64, i64), b: (i64, i64)| (a.0 - b.0).abs() + (a.1 - b.1).abs();
+    let hidden = (rng.range(1000000, 3000000), rng.range(1000000, 3000000));
+    let mut sensors = vec![];
+    for (dx, dy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let s = (
+            hidden.0 + dx * rng.range(100000, 900000),
+            hidden.1 + dy * rng.range(100000, 900000),
+        );
+        sensors.push((s, dist(s, hidden) - 1));
+    }
+    while sensors.len() < size.max(4) {
+        let s = (rng.range(0, MAX), rng.range(0, MAX));
+        let d = dist(s, hidden);
+        if d > 2 {
+            sensors.push((s, rng.range(d / 4, d - 2)));
+        }
+    }
+    rng.shuffle(&mut sensors);
+
+    lines(sensors.into_iter().map(|(s, range)| {
+        let dx = rng.range(0, range);
+        let dy = range - dx;
+        let b = (s.0 + rng.pick(&[-1, 1]) * dx, s.1 + rng.pick(&[-1, 1]) * dy);
+        format!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            s.0, s.1, b.0, b.1
+        )
+    }))
+}
+
+// A random tree of tunnels with some extra shortcuts. Only a few valves have
+// a flow rate, like in the real input.
+fn day16(rng: &mut Rng, size: usize) -> String {
+    let n = size.clamp(2, 62);
+    let mut names = vec!["AA".to_string()];
+    let mut used: HashSet<String> = names.iter().cloned().collect();
+    while names.len() < n {
+        let name = rng.letters(UPPER, 2);
+        if used.insert(name.clone()) {
+            names.push(name);
+        }
+    }
+
+    let mut tunnels: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut connect = |a: usize, b: usize| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+    for i in 1..n {
+        connect(i, rng.below(i));
+    }
+    for _ in 0..n / 3 {
+        connect(rng.below(n), rng.below(n));
+    }
+
+    let mut rates = vec![0; n];
+    let mut valves: Vec<usize> = (1..n).collect();
+    rng.shuffle(&mut valves);
+    for v in valves.into_iter().take(15.min(n / 3).max(1)) {
+        rates[v] = rng.range(1, 25);
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    rng.shuffle(&mut order);
+    lines(order.into_iter().map(|i| {
+        let to: Vec<&str> = tunnels[i].iter().map(|j| names[*j].as_str()).collect();
+        let (tunnels, leads, valves) = if to.len() == 1 {
+            ("tunnel", "leads", "valve")
+        } else {
+            ("tunnels", "lead", "valves")
+        };
+        format!(
+            "Valve {} has flow rate={}; {} {} to {} {}",
+            names[i],
+            rates[i],
+            tunnels,
+            leads,
+            valves,
+            to.join(", ")
+        )
+    }))
+}
+
+fn day17(rng: &mut Rng, size: usize) -> String {
+    rng.letters(b"<>", size.max(1)) + "\n"
+}
+
+// A random blob with about the same density as the real input
+fn day18(rng: &mut Rng, size: usize) -> String {
+    let side = ((size as f64 / 0.35).cbrt().ceil() as i64).max(1);
+    let mut cubes = HashSet::new();
+    let size = size.min((side * side * side) as usize).max(1);
+    while cubes.len() < size {
+        cubes.insert((
+            rng.range(0, side - 1),
+            rng.range(0, side - 1),
+            rng.range(0, side - 1),
+        ));
+    }
+    let mut cubes: Vec<_> = cubes.into_iter().collect();
+    cubes.sort();
+    rng.shuffle(&mut cubes);
+    lines(
+        cubes
+            .into_iter()
+            .map(|(x, y, z)| format!("{},{},{}", x, y, z)),
+    )
+}
+
+fn day19(rng: &mut Rng, size: usize) -> String {
+    lines((1..=size.max(1)).map(|i| {
+        format!(
+            "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+             Each obsidian robot costs {} ore and {} clay. \
+             Each geode robot costs {} ore and {} obsidian.",
+            i,
+            rng.range(2, 4),
+            rng.range(2, 4),
+            rng.range(2, 4),
+            rng.range(5, 20),
+            rng.range(2, 4),
+            rng.range(5, 20)
+        )
+    }))
+}
+
+// Exactly one zero, and something to move it around
+fn day20(rng: &mut Rng, size: usize) -> String {
+    let mut numbers: Vec<i64> = (1..size.max(2))
+        .map(|_| rng.range(1, 10000) * rng.pick(&[-1, 1]))
+        .collect();
+    numbers.insert(rng.below(numbers.len() + 1), 0);
+    lines(numbers.into_iter().map(|n| n.to_string()))
+}
+
+// The monkeys form a tree which is built top-down from the value each
+// monkey should yell, so that all divisions are exact. The humn monkey is a
+// leaf on one side of root, and both sides of root are equal.
+struct Monkeys<'a> {
+    rng: &'a mut Rng,
+    names: HashSet<String>,
+    jobs: Vec<String>,
+}
+
+impl Monkeys<'_> {
+    fn name(&mut self) -> String {
+        loop {
+            let name = self.rng.letters(LOWER, 4);
+            if name != "root" && name != "humn" && self.names.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+
+    // Returns the name of a monkey which yells `value`, using about `budget`
+    // monkeys
+    fn yell(&mut self, value: i64, budget: usize, humn: bool) -> String {
+        if budget <= 1 {
+            let name = if humn {
+                "humn".to_string()
+            } else {
+                self.name()
+            };
+            self.jobs.push(format!("{}: {}", name, value));
+            return name;
+        }
+
+        let b = self.rng.range(2, 20);
+        let (op, lhs, rhs) = match self.rng.below(4) {
+            0 if value > b => ('+', value - b, b),
+            1 if value % b == 0 => ('*', value / b, b),
+            2 if value < 1_000_000_000 => ('/', value * b, b),
+            _ => ('-', value + b, b),
+        };
+        let left = self.rng.below(budget - 1);
+        // Like in the real input, humn is never part of a divisor
+        let humn_left = humn && (op == '/' || self.rng.chance(0.5));
+        let lhs = self.yell(lhs, left.max(1), humn_left);
+        let rhs = self.yell(rhs, (budget - 1 - left).max(1), humn && !humn_left);
+
+        let name = self.name();
+        self.jobs.push(format!("{}: {} {} {}", name, lhs, op, rhs));
+        name
+    }
+}
+
+fn day21(rng: &mut Rng, size: usize) -> String {
+    let value = rng.range(100, 100000);
+    let budget = size.max(3) - 1;
+    let mut monkeys = Monkeys {
+        rng,
+        names: HashSet::new(),
+        jobs: vec![],
+    };
+    let lhs = monkeys.yell(value, budget / 2, true);
+    let rhs = monkeys.yell(value, budget - budget / 2, false);
+    let (lhs, rhs) = if monkeys.rng.chance(0.5) {
+        (lhs, rhs)
+    } else {
+        (rhs, lhs)
+    };
+    monkeys.jobs.push(format!("root: {} + {}", lhs, rhs));
+    monkeys.rng.shuffle(&mut monkeys.jobs);
+    lines(monkeys.jobs.into_iter())
+}
+
+// One of a few cube nets, with faces of `size` by `size` tiles
+fn day22(rng: &mut Rng, size: usize) -> String {
+    const NETS: [&[&str]; 4] = [
+        &[".##", ".#.", "##.", "#.."],
+        &["..#.", "###.", "..##"],
+        &[".#..", "####", ".#.."],
+        &["#...", "####", "...#"],
+    ];
+    let size = size.max(2);
+    let net = NETS[rng.below(NETS.len())];
+
+    let mut out = String::new();
+    for net_row in net {
+        for _ in 0..size {
+            let mut line = String::new();
+            for face in net_row.chars() {
+                for _ in 0..size {
+                    line.push(match face {
+                        '#' if rng.chance(0.1) => '#',
+                        '#' => '.',
+                        _ => ' ',
+                    });
+                }
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+    }
+    // The start tile is the first open tile on the first row
+    if !out.lines().next().unwrap().contains('.') {
+        let first = out.find('#').unwrap();
+        out.replace_range(first..first + 1, ".");
+    }
+
+    out.push('\n');
+    for i in 0..size * 4 {
+        write!(out, "{}", rng.range(1, 2 * size as i64)).unwrap();
+        if i + 1 < size * 4 {
+            out.push(rng.pick(&['L', 'R']));
+        }
+    }
+    out + "\n"
+}
+
+// At least one elf
+fn day23(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut rows: Vec<Vec<u8>> = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if rng.chance(0.45) { b'#' } else { b'.' })
+                .collect()
+        })
+        .collect();
+    if !rows.iter().flatten().any(|c| *c == b'#') {
+        rows[rng.below(size)][rng.below(size)] = b'#';
+    }
+    lines(rows.into_iter().map(|row| String::from_utf8(row).unwrap()))
+}
+
+// The columns with the entrance and the exit have no vertical blizzards,
+// like in the real input
+fn day24(rng: &mut Rng, size: usize) -> String {
+    let w = size.max(3);
+    let h = (w / 4).max(3);
+    let mut out = format!("#.{}\n", "#".repeat(w));
+    for _ in 0..h {
+        out.push('#');
+        for x in 1..=w {
+            let c = if !rng.chance(0.3) {
+                '.'
+            } else if x == 1 || x == w {
+                rng.pick(&['<', '>'])
+            } else {
+                rng.pick(&['<', '>', '^', 'v'])
+            };
+            out.push(c);
+        }
+        out.push_str("#\n");
+    }
+    out + &format!("{}.#\n", "#".repeat(w))
+}
+
+pub fn to_snafu(mut n: i64) -> String {
+    let mut digits = vec![];
+    while n != 0 {
+        let (digit, carry) = match n % 5 {
+            3 => ('=', 1),
+            4 => ('-', 1),
+            d => ((b'0' + d as u8) as char, 0),
+        };
+        digits.push(digit);
+        n = n / 5 + carry;
+    }
+    if digits.is_empty() {
+        digits.push('0');
+    }
+    digits.iter().rev().collect()
+}
+
+fn day25(rng: &mut Rng, size: usize) -> String {
+    lines((0..size.max(1)).map(|_| to_snafu(rng.range(1, 10_000_000_000_000))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::registry;
+    use crate::runner;
+    use crate::solution::Parts;
+    use std::time::Duration;
+
+    #[test]
+    fn every_day_solves() {
+        let registry = registry();
+        for day in 1..=25 {
+            // Keep the slow days small
+            let size = match day {
+                16 => 12,
+                18 => 300,
+                19 => 1,
+                _ => 20,
+            };
+            for seed in 0..2 {
+                let input = generate(day, Some(size), seed).unwrap();
+                let result = registry[&(2022, day)].run(input.as_bytes());
+                assert!(
+                    result.is_ok(),
+                    "day {} seed {}: {}",
+                    day,
+                    seed,
+                    result.unwrap_err()
+                );
+            }
+        }
+        assert_eq!(None, generate(26, None, 0));
+    }
+
+    // The smallest sizes are clamped to what the puzzles support
+    #[test]
+    fn smallest_sizes_solve() {
+        let registry = registry();
+        let timeout = Duration::from_secs(10);
+        let mut failures = vec![];
+        for day in 1..=25 {
+            for size in [0, 1] {
+                let input = generate(day, Some(size), 1).unwrap();
+                let p = &registry[&(2022, day)];
+                if let Err(err) = runner::solve_isolated(p, input.as_bytes(), Parts::Both, timeout)
+                {
+                    failures.push(format!("day {} size {}: {}", day, size, err));
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    // Part 2 should find the number humn was given
+    #[test]
+    fn day21_humn() {
+        let registry = registry();
+        for seed in 0..10 {
+            let input = generate(21, Some(200), seed).unwrap();
+            let humn = input
+                .lines()
+                .find_map(|l| l.strip_prefix("humn: "))
+                .unwrap();
+            let (answer, _) = registry[&(2022, 21)].run(input.as_bytes()).unwrap();
+            assert_eq!(humn, answer.part2);
+        }
+    }
+
+    #[test]
+    fn reproducible() {
+        assert_eq!(generate(20, Some(50), 7), generate(20, Some(50), 7));
+        assert_ne!(generate(20, Some(50), 7), generate(20, Some(50), 8));
+    }
+
+    #[test]
+    fn snafu() {
+        assert_eq!("1=-0-2", to_snafu(1747));
+        assert_eq!("2=-01", to_snafu(976));
+        assert_eq!("1", to_snafu(1));
+    }
+}
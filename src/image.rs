@@ -0,0 +1,289 @@
+// Images of visualization frames. Each cell becomes a square of pixels
+// colored by a palette, and frames are written as a sequence of PNG files or
+// as an animated GIF.
+//
+// All images of an animation have the same size: the largest frame, with
+// smaller frames padded with background.
+
+use crate::visualize::{self, Frame, Frames};
+use clap::Args;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+type Rgb = [u8; 3];
+
+// Colors for the cells, on top of the ones used in the terminal
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Palette {
+    colors: HashMap<char, Rgb>,
+    background: Option<Rgb>,
+}
+
+const BACKGROUND: Rgb = [16, 16, 16];
+const FOREGROUND: Rgb = [255, 255, 255];
+
+fn hex(s: &str) -> Option<Rgb> {
+    if s.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(s.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+impl Palette {
+    // Parses a comma separated list of CHAR=RRGGBB, where `bg` is the
+    // background (and the color of blank cells)
+    pub fn parse(spec: &str) -> Result<Palette, String> {
+        let mut palette = Palette::default();
+        for entry in spec.split(',').filter(|entry| !entry.is_empty()) {
+            let (key, color) = entry
+                .rsplit_once('=')
+                .ok_or_else(|| format!("expected CHAR=RRGGBB, not '{}'", entry))?;
+            let color = hex(color).ok_or_else(|| format!("'{}' is not a RRGGBB color", color))?;
+            let mut chars = key.chars();
+            match (key, chars.next(), chars.next()) {
+                ("bg", _, _) => palette.background = Some(color),
+                (_, Some(c), None) => {
+                    palette.colors.insert(c, color);
+                }
+                _ => return Err(format!("'{}' is not a single character", key)),
+            }
+        }
+        Ok(palette)
+    }
+
+    pub fn background(&self) -> Rgb {
+        self.background.unwrap_or(BACKGROUND)
+    }
+
+    pub fn color(&self, c: char) -> Rgb {
+        match self.colors.get(&c) {
+            Some(color) => *color,
+            None if c == ' ' || c == '\0' => self.background(),
+            None => visualize::color(c).unwrap_or(FOREGROUND),
+        }
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct Style {
+    /// Size of each cell in pixels, for --png and --gif
+    #[arg(long, value_name = "PIXELS", default_value_t = 4)]
+    pub cell: usize,
+
+    /// Colors for --png and --gif, as CHAR=RRGGBB pairs separated by
+    /// commas. `bg` sets the background.
+    #[arg(long, value_name = "COLORS", default_value = "", value_parser = Palette::parse)]
+    pub palette: Palette,
+}
+
+// The size of the images for some frames, and the characters they use
+#[derive(Debug, Default)]
+pub struct Canvas {
+    pub width: usize,
+    pub height: usize,
+    chars: BTreeSet<char>,
+}
+
+impl Canvas {
+    // Goes through every Nth frame
+    pub fn of(frames: Frames, every: usize) -> Canvas {
+        let mut canvas = Canvas::default();
+        for frame in frames.step_by(every.max(1)) {
+            canvas.width = canvas.width.max(frame.width());
+            canvas.height = canvas.height.max(frame.height());
+            for row in &frame.rows {
+                canvas.chars.extend(row.chars());
+            }
+        }
+        canvas.chars.insert(' ');
+        canvas
+    }
+
+    fn pixels(&self, cell: usize) -> (usize, usize) {
+        (self.width * cell.max(1), self.height * cell.max(1))
+    }
+
+    // The value of each pixel, row by row. Cells outside the frame are
+    // blank.
+    fn paint<T: Copy>(&self, frame: &Frame, cell: usize, value: impl Fn(char) -> T) -> Vec<T> {
+        let cell = cell.max(1);
+        let blank = value(' ');
+        let mut pixels = Vec::with_capacity(self.width * self.height * cell * cell);
+        for y in 0..self.height {
+            let mut line = Vec::with_capacity(self.width * cell);
+            let mut chars = frame.rows.get(y).map(|row| row.chars());
+            for _ in 0..self.width {
+                let c = chars.as_mut().and_then(|chars| chars.next());
+                let v = c.map_or(blank, &value);
+                line.extend(std::iter::repeat_n(v, cell));
+            }
+            for _ in 0..cell {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        pixels
+    }
+}
+
+fn write_png(path: &Path, frame: &Frame, canvas: &Canvas, style: &Style) -> Result<(), String> {
+    let failed =
+        |err: &dyn std::fmt::Display| format!("Failed to write {}: {}", path.display(), err);
+    let (width, height) = canvas.pixels(style.cell);
+    let file = File::create(path).map_err(|err| failed(&err))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let rgb = canvas.paint(frame, style.cell, |c| style.palette.color(c));
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(rgb.as_flattened()))
+        .map_err(|err| failed(&err))
+}
+
+// Writes every Nth frame to DIR/NNNNNN.png. Returns the number of frames
+// written.
+pub fn write_pngs(
+    frames: Frames,
+    canvas: &Canvas,
+    dir: &Path,
+    every: usize,
+    style: &Style,
+) -> Result<usize, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+    let mut count = 0;
+    for (n, frame) in frames.enumerate().step_by(every.max(1)) {
+        write_png(&dir.join(format!("{:06}.png", n)), &frame, canvas, style)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+// Writes every Nth frame to an animated GIF, which loops forever. Returns
+// the number of frames written.
+pub fn write_gif(
+    frames: Frames,
+    canvas: &Canvas,
+    path: &Path,
+    every: usize,
+    fps: f64,
+    style: &Style,
+) -> Result<usize, String> {
+    let failed =
+        |err: &dyn std::fmt::Display| format!("Failed to write {}: {}", path.display(), err);
+    let (width, height) = canvas.pixels(style.cell);
+    if width > u16::MAX as usize || height > u16::MAX as usize {
+        return Err(format!(
+            "{}x{} pixels is too large for a GIF, try a smaller --cell",
+            width, height
+        ));
+    }
+
+    // Every character gets an entry in the global color table
+    if canvas.chars.len() > 256 {
+        return Err(format!(
+            "{} colors is too many for a GIF",
+            canvas.chars.len()
+        ));
+    }
+    let index: HashMap<char, u8> = canvas
+        .chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (*c, i as u8))
+        .collect();
+    let colors: Vec<u8> = canvas
+        .chars
+        .iter()
+        .flat_map(|c| style.palette.color(*c))
+        .collect();
+
+    let file = File::create(path).map_err(|err| failed(&err))?;
+    let mut encoder = gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &colors)
+        .map_err(|err| failed(&err))?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(|err| failed(&err))?;
+
+    // The delay is in hundredths of a second, and most viewers don't go
+    // below 2
+    let delay = (100.0 / fps.max(0.01)).round().clamp(2.0, u16::MAX as f64) as u16;
+    let mut count = 0;
+    for frame in frames.step_by(every.max(1)) {
+        let pixels = canvas.paint(&frame, style.cell, |c| index.get(&c).copied().unwrap_or(0));
+        let gif_frame = gif::Frame {
+            width: width as u16,
+            height: height as u16,
+            delay,
+            buffer: Cow::Owned(pixels),
+            ..gif::Frame::default()
+        };
+        encoder
+            .write_frame(&gif_frame)
+            .map_err(|err| failed(&err))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames() -> Frames {
+        let frames = (1..=3).map(|n| Frame::draw(format!("{}", n), n, 2, |_, row| ['#', 'o'][row]));
+        Box::new(frames.collect::<Vec<_>>().into_iter())
+    }
+
+    #[test]
+    fn palette() {
+        let palette = Palette::parse("#=ff0000,bg=000010,==00ff00").unwrap();
+        assert_eq!([255, 0, 0], palette.color('#'));
+        assert_eq!([0, 255, 0], palette.color('='));
+        assert_eq!([0, 0, 16], palette.color(' '));
+        assert_eq!(visualize::color('o').unwrap(), palette.color('o'));
+        assert_eq!(Ok(Palette::default()), Palette::parse(""));
+        assert!(Palette::parse("#=red").is_err());
+        assert!(Palette::parse("ab=ffffff").is_err());
+    }
+
+    #[test]
+    fn export() {
+        let style = Style {
+            cell: 2,
+            palette: Palette::parse("o=0000ff").unwrap(),
+        };
+        let canvas = Canvas::of(frames(), 1);
+        assert_eq!((3, 2), (canvas.width, canvas.height));
+
+        let dir = std::env::temp_dir().join(format!("aoc-image-{}", std::process::id()));
+        assert_eq!(2, write_pngs(frames(), &canvas, &dir, 2, &style).unwrap());
+        let decoder = png::Decoder::new(File::open(dir.join("000002.png")).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((6, 4), (info.width, info.height));
+        // The bottom left pixel is an 'o'
+        assert_eq!([0, 0, 255], buf[3 * 6 * 3..3 * 6 * 3 + 3]);
+
+        let path = dir.join("frames.gif");
+        assert_eq!(
+            3,
+            write_gif(frames(), &canvas, &path, 1, 10.0, &style).unwrap()
+        );
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(File::open(&path).unwrap())
+            .unwrap();
+        let mut count = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!((6, 4, 10), (frame.width, frame.height, frame.delay));
+            count += 1;
+        }
+        assert_eq!(3, count);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,47 @@
+#![feature(test, int_roundings)]
+
+pub mod alloc;
+pub mod answers;
+pub mod crosscheck;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod error;
+pub mod generate;
+pub mod image;
+pub mod ocr;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod scale;
+pub mod select;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod solution;
+pub mod stats;
+pub mod visualize;
+pub mod watch;
+pub mod watchdog;
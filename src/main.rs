@@ -1,172 +1,380 @@
-#![feature(test, int_roundings)]
-
-use clap::Parser;
-use regex::Regex;
+use aoc2022rust::answers::AnswerDb;
+use aoc2022rust::crosscheck;
+use aoc2022rust::generate;
+use aoc2022rust::image::Style;
+use aoc2022rust::registry::{self, Puzzle};
+use aoc2022rust::report::{self, Baselines, Format};
+use aoc2022rust::runner::{self, Inputs, Limits, Options};
+use aoc2022rust::scaffold;
+use aoc2022rust::scale;
+use aoc2022rust::select::{self, Speed};
+#[cfg(feature = "serve")]
+use aoc2022rust::serve;
+use aoc2022rust::visualize::{self, Export, Playback};
+use aoc2022rust::watch;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 use std::time::Duration;
 
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
+#[derive(Subcommand)]
+enum Command {
+    /// Write a random input for DAY, for stress and scaling tests
+    Generate {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        /// Size of the input, in a unit which depends on the day (defaults
+        /// to about the size of the real input)
+        #[arg(short = 'n', long)]
+        size: Option<usize>,
+
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+
+        /// Write the input to FILE instead of stdout
+        #[arg(short = 'o', long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Time DAY over inputs of increasing size (DIR/dayNN/N.txt) and fit a
+    /// complexity exponent. Writes the timings as CSV.
+    Scale {
+        day: u8,
+
+        #[arg(long, value_name = "DIR", default_value = "inputs/scale")]
+        dir: PathBuf,
+
+        /// Generate any missing inputs of these sizes first
+        #[arg(long, value_name = "SIZES", value_delimiter = ',')]
+        generate: Vec<usize>,
+
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+
+        /// Write the CSV to FILE instead of stdout
+        #[arg(short = 'o', long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Print the answers for DAY (DAY:2 for part 2 only) for an input read
+    /// from stdin, without benchmarking or checking them
+    Solve {
+        day: String,
+
+        /// Read the input from FILE instead of stdin
+        #[arg(long, value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+
+    /// Answer puzzles over HTTP on localhost: POST an input to /solve/DAY,
+    /// or GET /days for the days there are solutions for. Puzzles are
+    /// given up on after --timeout seconds.
+    #[cfg(feature = "serve")]
+    Serve {
+        #[arg(long, default_value_t = 8022)]
+        port: u16,
+
+        /// Number of puzzles which may run at once (default: one per CPU)
+        #[arg(short = 'j', long, value_name = "N")]
+        jobs: Option<usize>,
+    },
+
+    /// Rebuild and re-run PUZZLES whenever their sources or inputs change,
+    /// showing what changed since the previous run
+    Watch {
+        /// Puzzles to run, as for the runner
+        puzzles: Vec<String>,
+
+        /// How often to look for changes
+        #[arg(long, value_name = "MS", default_value_t = 500)]
+        interval: u64,
+
+        /// The crate to watch and build
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        root: PathBuf,
+    },
+
+    /// Compare the answers for PUZZLES with those of reference solutions,
+    /// on the real input or on generated ones
+    Crosscheck {
+        /// Puzzles to check, as for the runner
+        puzzles: Vec<String>,
+
+        /// File with the reference command for each day
+        #[arg(long, value_name = "FILE", default_value = "crosscheck.toml")]
+        config: PathBuf,
+
+        /// Check this many generated inputs per day instead of the real one
+        #[arg(long, value_name = "N")]
+        generate: Option<u64>,
+
+        /// Size of the generated inputs (see generate)
+        #[arg(short = 'n', long, requires = "generate")]
+        size: Option<usize>,
+
+        /// Seed of the first generated input
+        #[arg(long, default_value_t = 1, requires = "generate")]
+        seed: u64,
+    },
+
+    /// Add a skeleton solution for DAY, with an empty input and example,
+    /// and register it
+    NewDay {
+        day: u8,
+
+        #[arg(long, default_value_t = registry::DEFAULT_YEAR)]
+        year: u16,
+
+        /// The crate to add the day to
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        root: PathBuf,
+    },
+
+    /// Add a module for the puzzles of YEAR, to add days to with new-day
+    NewYear {
+        year: u16,
+
+        /// The crate to add the year to
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        root: PathBuf,
+    },
+}
+
 #[derive(Parser)]
 struct Cli {
-    #[arg(short = 'r', long, default_value_t = 1)]
-    max_reps: usize,
+    #[command(subcommand)]
+    command: Option<Command>,
 
-    #[arg(short = 's', long, default_value_t = 5)]
-    max_secs: u64,
+    #[command(flatten)]
+    limits: Limits,
 
-    puzzles: Vec<String>,
-}
+    /// Give up on a puzzle if a single run takes longer than SECS
+    #[arg(short = 't', long, value_name = "SECS", default_value_t = 60)]
+    timeout: u64,
 
-struct Puzzle {
-    name: String,
-    fun: fn(),
-}
+    #[command(flatten)]
+    inputs: Inputs,
 
-impl Puzzle {
-    fn make(day: i32, fun: fn()) -> Puzzle {
-        Puzzle {
-            name: format!("{:02}", day),
-            fun,
-        }
-    }
-}
+    #[arg(short = 'f', long, value_enum, default_value_t = Format::Table)]
+    format: Format,
 
-fn run_puzzles(puzzles: Vec<Puzzle>, args: &Cli) {
-    let mut total_runtime = Duration::default();
-    let len = puzzles.len();
-    for p in puzzles {
-        let mut runtimes: Vec<Duration> = vec![];
-        let start = std::time::Instant::now();
-
-        loop {
-            let t = std::time::Instant::now();
-            (p.fun)();
-            runtimes.push(t.elapsed());
-            if start.elapsed().as_secs() >= args.max_secs || runtimes.len() >= args.max_reps {
-                break;
-            }
-        }
+    #[command(flatten)]
+    baselines: Baselines,
 
-        let avg_runtime = runtimes.iter().sum::<Duration>() / runtimes.len() as u32;
-        println!(
-            "Day {}: {:10} μs {:10} ns ({} reps)",
-            p.name,
-            avg_runtime.as_micros(),
-            avg_runtime.as_nanos(),
-            runtimes.len()
-        );
-        total_runtime += avg_runtime;
-    }
+    /// File with known answers, keyed by day and input hash
+    #[arg(long, value_name = "FILE", default_value = "answers.toml")]
+    answers: PathBuf,
 
-    println!(
-        "Average runtime per puzzle: {:.2} ms",
-        total_runtime.as_millis() as f64 / len as f64
-    );
+    /// Add answers for inputs which are not in the answers file yet
+    #[arg(long)]
+    record: bool,
 
-    println!("Total runtime: {} ms", total_runtime.as_millis());
-}
+    /// Run the puzzles concurrently. Timings are not comparable to a
+    /// sequential run.
+    #[arg(long, conflicts_with_all = ["save_baseline", "baseline"])]
+    parallel: bool,
 
-fn day5_sol() -> (String, String) {
-    ("CNSZFDVLJ".to_string(), "QNDWLMGNS".to_string())
-}
+    /// Number of threads for --parallel (default: one per CPU)
+    #[arg(short = 'j', long, value_name = "N", requires = "parallel")]
+    jobs: Option<usize>,
+
+    /// Only run the slow puzzles
+    #[arg(long, conflicts_with = "fast")]
+    slow: bool,
+
+    /// Only run the fast puzzles
+    #[arg(long)]
+    fast: bool,
+
+    /// Year of the days which are given without one (7 is YEAR:7)
+    #[arg(long, default_value_t = registry::DEFAULT_YEAR)]
+    year: u16,
+
+    /// Show the simulation of DAY (or DAY:2 for part 2, YEAR:DAY for
+    /// another year) frame by frame,
+    /// instead of running puzzles
+    #[arg(long, value_name = "DAY")]
+    visualize: Option<String>,
+
+    #[command(flatten)]
+    export: Export,
+
+    #[command(flatten)]
+    playback: Playback,
 
-fn day10_sol() -> (i64, String) {
-    (
-        14060,
-        "\
-███...██..███..█..█.████.█..█.████...██.\
-█..█.█..█.█..█.█.█..█....█.█..█.......█.\
-█..█.█..█.█..█.██...███..██...███.....█.\
-███..████.███..█.█..█....█.█..█.......█.\
-█....█..█.█....█.█..█....█.█..█....█..█.\
-█....█..█.█....█..█.█....█..█.████..██..\
-"
-        .to_string(),
-    )
+    #[command(flatten)]
+    style: Style,
+
+    /// Puzzles to run: days (7), ranges (1-5), lists (7,9,12), single
+    /// parts (15:2), exclusions (!19), days of a year (2022:7) and whole
+    /// years (2022). Runs all puzzles by default.
+    puzzles: Vec<String>,
 }
 
-fn matches(cli: &Cli, name: &String) -> bool {
-    for p in &cli.puzzles {
-        if let Ok(re) = Regex::new(name) {
-            if re.is_match(p) {
-                return true;
+fn scaffold_done(result: Result<Vec<PathBuf>, String>) {
+    match result {
+        Ok(paths) => {
+            for path in paths {
+                println!("Wrote {}", path.display());
             }
         }
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn exit_on_error(result: Result<(), String>) {
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
     }
-    false
 }
 
 fn main() {
     let args = Cli::parse();
-    let all_puzzles = vec![
-        Puzzle::make(1, || assert_eq!((69836, 207968), day01::solve())),
-        Puzzle::make(2, || assert_eq!((14297, 10498), day02::solve())),
-        Puzzle::make(3, || assert_eq!((8349, 2681), day03::solve())),
-        Puzzle::make(4, || assert_eq!((582, 893), day04::solve())),
-        Puzzle::make(5, || assert_eq!(day5_sol(), day05::solve())),
-        Puzzle::make(6, || assert_eq!((1802, 3551), day06::solve())),
-        Puzzle::make(7, || assert_eq!((1543140, 1117448), day07::solve())),
-        Puzzle::make(8, || assert_eq!((1684, 486540), day08::solve())),
-        Puzzle::make(9, || assert_eq!((6311, 2482), day09::solve())),
-        Puzzle::make(10, || assert_eq!(day10_sol(), day10::solve())),
-        Puzzle::make(11, || assert_eq!((102399, 23641658401), day11::solve())),
-        Puzzle::make(12, || assert_eq!((370, 363), day12::solve())),
-        Puzzle::make(13, || assert_eq!((5198, 22344), day13::solve())),
-        Puzzle::make(14, || assert_eq!((696, 23610), day14::solve())),
-        Puzzle::make(15, || assert_eq!((4665948, 13543690671045), day15::solve())),
-        Puzzle::make(16, || assert_eq!((1376, 1933), day16::solve())),
-        Puzzle::make(17, || assert_eq!((3153, 1553665689155), day17::solve())),
-        Puzzle::make(18, || assert_eq!((3530, 2000), day18::solve())),
-        Puzzle::make(19, || assert_eq!((1382, 31740), day19::solve())),
-        Puzzle::make(20, || assert_eq!((7278, 14375678667089), day20::solve())),
-        Puzzle::make(21, || {
-            assert_eq!((268597611536314, 3451534022348), day21::solve())
-        }),
-        Puzzle::make(22, || assert_eq!((56372, 197047), day22::solve())),
-        Puzzle::make(23, || assert_eq!((3684, 862), day23::solve())),
-        Puzzle::make(24, || assert_eq!((245, 798), day24::solve())),
-        Puzzle::make(25, || assert_eq!(("2=0=02-0----2-=02-10"), day25::solve())),
-    ];
-    if args.puzzles.len() == 0 {
-        println!("Running all puzzles.");
-        run_puzzles(all_puzzles, &args);
-    } else {
-        // Only run specified puzzles
-        let subset: Vec<Puzzle> = all_puzzles
-            .into_iter()
-            .filter(|p| matches(&args, &p.name))
-            .collect();
-
-        if subset.len() == 0 {
-            println!(
-                "Puzzles specified do not match any implementations: {:?}",
-                args.puzzles
-            );
-        } else {
-            run_puzzles(subset, &args);
+    let timeout = Duration::from_secs(args.timeout);
+    match &args.command {
+        Some(Command::Generate {
+            day,
+            size,
+            seed,
+            output,
+        }) => return exit_on_error(generate::write_input(*day, *size, *seed, output.as_deref())),
+        Some(Command::Scale {
+            day,
+            dir,
+            generate,
+            seed,
+            output,
+        }) => {
+            return exit_on_error(scale::run_scale(
+                *day,
+                dir,
+                generate,
+                *seed,
+                output.as_deref(),
+                &args.limits,
+                timeout,
+            ));
+        }
+        Some(Command::Solve { day, file }) => {
+            return exit_on_error(runner::run_solve(day, args.year, file.as_deref(), timeout));
+        }
+        #[cfg(feature = "serve")]
+        Some(Command::Serve { port, jobs }) => {
+            let jobs = jobs.unwrap_or_else(rayon::current_num_threads);
+            let state = serve::State::new(timeout, jobs);
+            return exit_on_error(serve::serve(*port, state));
         }
+        Some(Command::Watch {
+            puzzles,
+            interval,
+            root,
+        }) => {
+            let interval = Duration::from_millis(*interval);
+            return exit_on_error(watch::run_watch(
+                puzzles,
+                interval,
+                root,
+                args.year,
+                timeout,
+                &args.answers,
+            ));
+        }
+        Some(Command::Crosscheck {
+            puzzles,
+            config,
+            generate,
+            size,
+            seed,
+        }) => {
+            let inputs = generate.map(|count| (*seed..*seed + count, *size));
+            match crosscheck::run_crosscheck(
+                puzzles,
+                config,
+                inputs,
+                args.year,
+                &args.inputs,
+                timeout,
+            ) {
+                Ok(true) => return,
+                Ok(false) => std::process::exit(1),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(2);
+                }
+            }
+        }
+        Some(Command::NewDay { day, year, root }) => {
+            return scaffold_done(scaffold::new_day(root, *year, *day));
+        }
+        Some(Command::NewYear { year, root }) => {
+            return scaffold_done(scaffold::new_year(root, *year));
+        }
+        None => {}
+    }
+    if let Some(selector) = &args.visualize {
+        return exit_on_error(visualize::run_visualize(
+            selector,
+            args.year,
+            &args.inputs,
+            &args.export,
+            &args.playback,
+            &args.style,
+        ));
+    }
+
+    let registry = registry::registry();
+    let mut db = match AnswerDb::load(&args.answers) {
+        Ok(db) => db,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+
+    let speed = match (args.slow, args.fast) {
+        (true, _) => Some(Speed::Slow),
+        (_, true) => Some(Speed::Fast),
+        _ => None,
+    };
+    let puzzles: Vec<&Puzzle> = registry.values().collect();
+    let subset = match select::select(&args.puzzles, &puzzles, speed, args.year) {
+        Ok(subset) => subset,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+
+    if args.inputs.input.is_some() && subset.len() != 1 {
+        eprintln!("--input requires exactly one puzzle to be specified.");
+        std::process::exit(2);
+    }
+    if subset.is_empty() {
+        println!(
+            "Puzzles specified do not match any implementations: {:?}",
+            args.puzzles
+        );
+        return;
+    }
+
+    if args.puzzles.is_empty() && speed.is_none() && args.format == Format::Table {
+        println!("Running all puzzles.");
     }
+    let options = Options {
+        limits: args.limits,
+        timeout,
+        inputs: args.inputs.clone(),
+        format: args.format,
+        parallel: args.parallel,
+        jobs: args.jobs,
+        record: args.record,
+        answers: args.answers.clone(),
+    };
+    let (results, failures) = runner::run_puzzles(subset, &options, &mut db);
+    args.baselines.handle(&results);
+    std::process::exit(report::exit_code(&results, &failures));
 }
@@ -0,0 +1,149 @@
+// Reading the letters on the day 10 CRT. Advent of Code draws capital
+// letters 4 pixels wide and 6 high, with a blank column after each letter.
+
+use std::fmt;
+
+pub const HEIGHT: usize = 6;
+// Including the blank column
+const WIDTH: usize = 5;
+
+// The letters which are known to appear in answers
+const FONT: [(char, [&str; HEIGHT]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+pub const LETTERS: &str = "ABCEFGHIJKLOPRSUZ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    // Number of pixels which do not make up whole letters
+    Size(usize),
+    // The letter at `position` (from 0), drawn with '#' and '.'
+    UnknownGlyph { position: usize, glyph: Vec<String> },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::Size(pixels) => write!(
+                f,
+                "{} pixels is not {} rows of {} pixel wide letters",
+                pixels, HEIGHT, WIDTH
+            ),
+            OcrError::UnknownGlyph { position, glyph } => {
+                write!(f, "unknown glyph at letter {}:", position + 1)?;
+                for row in glyph {
+                    write!(f, "\n{}", row)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+// Reads a screen given row by row, with or without line breaks. Pixels
+// other than '.' and ' ' are lit.
+pub fn read(screen: &str) -> Result<String, OcrError> {
+    let pixels: Vec<bool> = screen
+        .chars()
+        .filter(|c| *c != '\n')
+        .map(|c| c != '.' && c != ' ')
+        .collect();
+    let width = pixels.len() / HEIGHT;
+    if pixels.is_empty() || !pixels.len().is_multiple_of(HEIGHT) || !width.is_multiple_of(WIDTH) {
+        return Err(OcrError::Size(pixels.len()));
+    }
+
+    (0..width / WIDTH)
+        .map(|position| {
+            let glyph: Vec<String> = (0..HEIGHT)
+                .map(|row| {
+                    let start = row * width + position * WIDTH;
+                    pixels[start..start + WIDTH]
+                        .iter()
+                        .map(|lit| if *lit { '#' } else { '.' })
+                        .collect()
+                })
+                .collect();
+            FONT.iter()
+                .find(|(_, rows)| {
+                    rows.iter()
+                        .zip(&glyph)
+                        .all(|(font, row)| row.starts_with(font) && row.ends_with('.'))
+                })
+                .map(|(c, _)| *c)
+                .ok_or(OcrError::UnknownGlyph { position, glyph })
+        })
+        .collect()
+}
+
+// Draws `text` the way the CRT would, row by row without line breaks. Returns
+// None if a letter is not in the font.
+pub fn draw(text: &str) -> Option<String> {
+    let glyphs = text
+        .chars()
+        .map(|c| {
+            FONT.iter()
+                .find(|(letter, _)| *letter == c)
+                .map(|(_, rows)| rows)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let mut screen = String::new();
+    for row in 0..HEIGHT {
+        for glyph in &glyphs {
+            screen.push_str(glyph[row]);
+            screen.push('.');
+        }
+    }
+    Some(screen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters() {
+        assert_eq!(LETTERS, FONT.iter().map(|(c, _)| *c).collect::<String>());
+        assert_eq!(Ok(LETTERS.to_string()), read(&draw(LETTERS).unwrap()));
+        assert_eq!(None, draw("AXE"));
+
+        // The CRT's own pixels, and line breaks, are fine
+        let pixels: Vec<char> = draw("HI").unwrap().replace('#', "█").chars().collect();
+        let rows: Vec<String> = pixels.chunks(10).map(|row| row.iter().collect()).collect();
+        assert_eq!(Ok("HI".to_string()), read(&rows.join("\n")));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(OcrError::Size(239)), read(&".".repeat(239)));
+        assert_eq!(Err(OcrError::Size(0)), read(""));
+
+        let mut screen = draw("OK").unwrap().into_bytes();
+        screen[10 + 6] = b'#';
+        let err = read(std::str::from_utf8(&screen).unwrap()).unwrap_err();
+        assert!(matches!(err, OcrError::UnknownGlyph { position: 1, .. }));
+        assert_eq!(
+            "unknown glyph at letter 2:\n#..#.\n###..\n##...\n#.#..\n#.#..\n#..#.",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::error::ParseResult;
+use crate::solution::{self, Answers, Parts, Solution, SolveFn, Timings};
+use crate::*;
+use std::collections::BTreeMap;
+
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+    pub input: &'static [u8],
+    pub solve: SolveFn,
+    pub slow: bool,
+}
+
+// The year of days which are given without one. Its inputs and answers are
+// kept without the year in their names.
+pub const DEFAULT_YEAR: u16 = 2022;
+
+// "15" for a day of the default year, "2023-15" otherwise
+pub fn name(year: u16, day: u8) -> String {
+    if year == DEFAULT_YEAR {
+        format!("{:02}", day)
+    } else {
+        format!("{}-{:02}", year, day)
+    }
+}
+
+// Days of 2022 which take more than about 20 ms
+const SLOW_DAYS: [u8; 6] = [16, 18, 19, 20, 23, 24];
+
+impl Puzzle {
+    pub(crate) fn make<S: Solution>(year: u16, day: u8, input: &'static [u8]) -> Puzzle {
+        Puzzle {
+            year,
+            day,
+            input,
+            solve: solution::solve_parts::<S>,
+            slow: year == 2022 && SLOW_DAYS.contains(&day),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        name(self.year, self.day)
+    }
+
+    // The solution, relative to the crate root
+    pub fn source_file(&self) -> String {
+        if self.year == DEFAULT_YEAR {
+            format!("src/day{:02}.rs", self.day)
+        } else {
+            format!("src/y{}/day{:02}.rs", self.year, self.day)
+        }
+    }
+
+    // The input file, relative to the inputs directory
+    pub fn input_file(&self) -> String {
+        if self.year == DEFAULT_YEAR {
+            format!("input{:02}.txt", self.day)
+        } else {
+            format!("{}/input{:02}.txt", self.year, self.day)
+        }
+    }
+
+    // Parse errors are tagged with the year and day
+    pub fn run(&self, input: &[u8]) -> ParseResult<(Answers, Timings)> {
+        (self.solve)(input, Parts::Both).map_err(|err| err.in_day(self.year, self.day))
+    }
+}
+
+pub type Registry = BTreeMap<(u16, u8), Puzzle>;
+
+pub fn registry() -> Registry {
+    let puzzles = vec![
+        Puzzle::make::<day01::Day01>(2022, 1, include_bytes!("../inputs/input01.txt")),
+        Puzzle::make::<day02::Day02>(2022, 2, include_bytes!("../inputs/input02.txt")),
+        Puzzle::make::<day03::Day03>(2022, 3, include_bytes!("../inputs/input03.txt")),
+        Puzzle::make::<day04::Day04>(2022, 4, include_bytes!("../inputs/input04.txt")),
+        Puzzle::make::<day05::Day05>(2022, 5, include_bytes!("../inputs/input05.txt")),
+        Puzzle::make::<day06::Day06>(2022, 6, include_bytes!("../inputs/input06.txt")),
+        Puzzle::make::<day07::Day07>(2022, 7, include_bytes!("../inputs/input07.txt")),
+        Puzzle::make::<day08::Day08>(2022, 8, include_bytes!("../inputs/input08.txt")),
+        Puzzle::make::<day09::Day09>(2022, 9, include_bytes!("../inputs/input09.txt")),
+        Puzzle::make::<day10::Day10>(2022, 10, include_bytes!("../inputs/input10.txt")),
+        Puzzle::make::<day11::Day11>(2022, 11, include_bytes!("../inputs/input11.txt")),
+        Puzzle::make::<day12::Day12>(2022, 12, include_bytes!("../inputs/input12.txt")),
+        Puzzle::make::<day13::Day13>(2022, 13, include_bytes!("../inputs/input13.txt")),
+        Puzzle::make::<day14::Day14>(2022, 14, include_bytes!("../inputs/input14.txt")),
+        Puzzle::make::<day15::Day15>(2022, 15, include_bytes!("../inputs/input15.txt")),
+        Puzzle::make::<day16::Day16>(2022, 16, include_bytes!("../inputs/input16.txt")),
+        Puzzle::make::<day17::Day17>(2022, 17, include_bytes!("../inputs/input17.txt")),
+        Puzzle::make::<day18::Day18>(2022, 18, include_bytes!("../inputs/input18.txt")),
+        Puzzle::make::<day19::Day19>(2022, 19, include_bytes!("../inputs/input19.txt")),
+        Puzzle::make::<day20::Day20>(2022, 20, include_bytes!("../inputs/input20.txt")),
+        Puzzle::make::<day21::Day21>(2022, 21, include_bytes!("../inputs/input21.txt")),
+        Puzzle::make::<day22::Day22>(2022, 22, include_bytes!("../inputs/input22.txt")),
+        Puzzle::make::<day23::Day23>(2022, 23, include_bytes!("../inputs/input23.txt")),
+        Puzzle::make::<day24::Day24>(2022, 24, include_bytes!("../inputs/input24.txt")),
+        Puzzle::make::<day25::Day25>(2022, 25, include_bytes!("../inputs/input25.txt")),
+        // New days are added above this line
+    ];
+
+    puzzles
+        .into_iter()
+        // New years are added above this line
+        .map(|p| ((p.year, p.day), p))
+        .collect()
+}
+
+pub fn find(registry: &Registry, year: u16, day: u8) -> Result<&Puzzle, String> {
+    registry
+        .get(&(year, day))
+        .ok_or_else(|| format!("no such day: {}", name(year, day)))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn all_days_registered() {
+        let registry = super::registry();
+        assert_eq!(25, registry.len());
+        for (day, (key, puzzle)) in (1..=25).zip(&registry) {
+            assert_eq!((2022, day), *key);
+            assert_eq!(day, puzzle.day);
+        }
+    }
+}
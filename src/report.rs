@@ -0,0 +1,436 @@
+use crate::alloc::{self, MemStats};
+use crate::answers::Check;
+use crate::registry::{self, Puzzle};
+use crate::solution::{Answers, Timings};
+use crate::stats::PuzzleStats;
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const BASELINE_DIR: &str = "baselines";
+
+// Benchmark result for a single day, in a form suitable for saving
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayResult {
+    pub year: u16,
+    pub day: u8,
+    pub part1: String,
+    pub part2: String,
+    #[serde(default)]
+    pub part1_check: Check,
+    #[serde(default)]
+    pub part2_check: Check,
+    pub parse_ns: u64,
+    pub part1_ns: u64,
+    pub part2_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub min_ns: u64,
+    pub max_ns: u64,
+    pub stddev_ns: u64,
+    pub p90_ns: u64,
+    pub p99_ns: u64,
+    pub reps: usize,
+    pub outliers: usize,
+    pub converged: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemStats>,
+}
+
+impl DayResult {
+    pub fn new(
+        p: &Puzzle,
+        answer: &Answers,
+        checks: (Check, Check),
+        s: &PuzzleStats,
+        converged: bool,
+        memory: Option<MemStats>,
+    ) -> DayResult {
+        DayResult {
+            year: p.year,
+            day: p.day,
+            part1: answer.part1.clone(),
+            part2: answer.part2.clone(),
+            part1_check: checks.0,
+            part2_check: checks.1,
+            parse_ns: s.parse.median.as_nanos() as u64,
+            part1_ns: s.part1.median.as_nanos() as u64,
+            part2_ns: s.part2.median.as_nanos() as u64,
+            median_ns: s.total.median.as_nanos() as u64,
+            mean_ns: s.total.mean.as_nanos() as u64,
+            min_ns: s.total.min.as_nanos() as u64,
+            max_ns: s.total.max.as_nanos() as u64,
+            stddev_ns: s.total.stddev.as_nanos() as u64,
+            p90_ns: s.total.p90.as_nanos() as u64,
+            p99_ns: s.total.p99.as_nanos() as u64,
+            reps: s.total.reps,
+            outliers: s.total.outliers,
+            converged,
+            memory,
+        }
+    }
+}
+
+pub fn to_json(results: &[DayResult]) -> String {
+    serde_json::to_string_pretty(results).unwrap()
+}
+
+pub fn to_csv(results: &[DayResult]) -> String {
+    let mut out = String::from(
+        "year,day,part1_check,part2_check,parse_ns,part1_ns,part2_ns,median_ns,mean_ns,min_ns,max_ns,\
+         stddev_ns,p90_ns,p99_ns,reps,outliers,converged,peak_bytes,total_bytes,allocations\n",
+    );
+    for r in results {
+        // Memory columns are empty unless allocations were counted
+        let memory = match r.memory {
+            Some(m) => format!("{},{},{}", m.peak_bytes, m.total_bytes, m.allocations),
+            None => ",,".to_string(),
+        };
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            r.year,
+            r.day,
+            r.part1_check,
+            r.part2_check,
+            r.parse_ns,
+            r.part1_ns,
+            r.part2_ns,
+            r.median_ns,
+            r.mean_ns,
+            r.min_ns,
+            r.max_ns,
+            r.stddev_ns,
+            r.p90_ns,
+            r.p99_ns,
+            r.reps,
+            r.outliers,
+            r.converged,
+            memory
+        ));
+    }
+    out
+}
+
+pub fn to_markdown(results: &[DayResult]) -> String {
+    let us = |ns: u64| format!("{:.1}", ns as f64 / 1000.0);
+    let mut out = String::from(
+        "| Day | Parse (μs) | Part 1 (μs) | Part 2 (μs) | Median (μs) | Min (μs) | Max (μs) | Stddev (μs) | Reps | Answers |\n\
+         |----:|-----------:|------------:|------------:|------------:|---------:|---------:|------------:|-----:|---------|\n",
+    );
+    for r in results {
+        out.push_str(&format!(
+            "| {}/{:02} | {} | {} | {} | {} | {} | {} | {} | {} | {}/{} |\n",
+            r.year,
+            r.day,
+            us(r.parse_ns),
+            us(r.part1_ns),
+            us(r.part2_ns),
+            us(r.median_ns),
+            us(r.min_ns),
+            us(r.max_ns),
+            us(r.stddev_ns),
+            r.reps,
+            r.part1_check,
+            r.part2_check
+        ));
+    }
+    out
+}
+
+fn baseline_path(name: &str) -> PathBuf {
+    PathBuf::from(BASELINE_DIR).join(format!("{}.json", name))
+}
+
+pub fn save_baseline(name: &str, results: &[DayResult]) -> Result<PathBuf, String> {
+    let path = baseline_path(name);
+    std::fs::create_dir_all(BASELINE_DIR)
+        .and_then(|_| std::fs::write(&path, to_json(results)))
+        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+    Ok(path)
+}
+
+pub fn load_baseline(name: &str) -> Result<Vec<DayResult>, String> {
+    let path = baseline_path(name);
+    let text = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    serde_json::from_str(&text).map_err(|err| format!("Bad baseline {}: {}", path.display(), err))
+}
+
+// Compares median runtimes against a baseline. Days which got slower by
+// more than `threshold` percent are marked as regressions. Returns the
+// report and the number of regressions.
+pub fn compare(baseline: &[DayResult], results: &[DayResult], threshold: f64) -> (String, usize) {
+    let mut out = String::new();
+    let mut regressions = 0;
+
+    for r in results {
+        let old = baseline.iter().find(|b| b.year == r.year && b.day == r.day);
+        let line = match old {
+            None => format!("Day {}: not in baseline", registry::name(r.year, r.day)),
+            Some(old) => {
+                let delta = if old.median_ns == 0 {
+                    0.0
+                } else {
+                    (r.median_ns as f64 - old.median_ns as f64) / old.median_ns as f64 * 100.0
+                };
+                let marker = if delta > threshold {
+                    regressions += 1;
+                    "REGRESSION"
+                } else if delta < -threshold {
+                    "improved"
+                } else {
+                    ""
+                };
+                format!(
+                    "Day {}: {:12.1} -> {:12.1} μs {:+7.1}% {}",
+                    registry::name(r.year, r.day),
+                    old.median_ns as f64 / 1000.0,
+                    r.median_ns as f64 / 1000.0,
+                    delta,
+                    marker
+                )
+            }
+        };
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    (out, regressions)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+// Where the results are saved and compared to, if anywhere
+#[derive(Args, Clone, Default)]
+pub struct Baselines {
+    /// Save the results as baseline NAME
+    #[arg(long, value_name = "NAME")]
+    pub save_baseline: Option<String>,
+
+    /// Compare the results against baseline NAME
+    #[arg(long, value_name = "NAME")]
+    pub baseline: Option<String>,
+
+    /// Slowdown (in percent) against the baseline counted as a regression
+    #[arg(long, default_value_t = 5.0)]
+    pub threshold: f64,
+}
+
+impl Baselines {
+    // Saves and/or compares against baselines, as requested
+    pub fn handle(&self, results: &[DayResult]) {
+        if let Some(name) = &self.baseline {
+            match load_baseline(name) {
+                Ok(baseline) => {
+                    let (text, regressions) = compare(&baseline, results, self.threshold);
+                    eprintln!("\nCompared to baseline '{}':", name);
+                    eprint!("{}", text);
+                    if regressions > 0 {
+                        eprintln!(
+                            "{} regression(s) over {:.1}% threshold",
+                            regressions, self.threshold
+                        );
+                    }
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+
+        if let Some(name) = &self.save_baseline {
+            match save_baseline(name, results) {
+                Ok(path) => eprintln!("Saved baseline to {}", path.display()),
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+    }
+}
+
+pub fn us(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1000.0
+}
+
+// Byte counts with a binary suffix, e.g. 12.5K
+pub fn bytes(n: usize) -> String {
+    let mut value = n as f64;
+    for suffix in ["", "K", "M", "G"] {
+        if value < 1024.0 || suffix == "G" {
+            return format!("{:.1}{}", value, suffix);
+        }
+        value /= 1024.0;
+    }
+    unreachable!()
+}
+
+pub fn print_header() {
+    // Memory columns are only shown when allocations are counted
+    let memory = if alloc::ENABLED {
+        format!(" {:>8} {:>8} {:>8}", "peak", "alloc'd", "allocs")
+    } else {
+        String::new()
+    };
+    println!(
+        "{:8} {:>10} {:>10} {:>10} | {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>6}{}",
+        "(μs)",
+        "parse",
+        "part 1",
+        "part 2",
+        "median",
+        "mean",
+        "min",
+        "max",
+        "stddev",
+        "p90",
+        "p99",
+        "reps",
+        memory
+    );
+}
+
+pub fn print_stats(label: &str, s: &PuzzleStats, converged: bool, memory: Option<MemStats>) {
+    let mut flags = vec![];
+    if s.total.outliers > 0 {
+        flags.push(format!("{} outliers", s.total.outliers));
+    }
+    if !converged {
+        flags.push(format!(
+            "not converged (±{:.1}%)",
+            s.total.relative_error() * 100.0
+        ));
+    }
+
+    let memory = match memory {
+        Some(m) => format!(
+            " {:>8} {:>8} {:>8}",
+            bytes(m.peak_bytes),
+            bytes(m.total_bytes),
+            m.allocations
+        ),
+        None if alloc::ENABLED => format!(" {:>8} {:>8} {:>8}", "-", "-", "-"),
+        None => String::new(),
+    };
+
+    let line = format!(
+        "{:8} {:10.1} {:10.1} {:10.1} | {:10.1} {:10.1} {:10.1} {:10.1} {:10.1} {:10.1} {:10.1} {:6}{} {}",
+        label,
+        us(s.parse.median),
+        us(s.part1.median),
+        us(s.part2.median),
+        us(s.total.median),
+        us(s.total.mean),
+        us(s.total.min),
+        us(s.total.max),
+        us(s.total.stddev),
+        us(s.total.p90),
+        us(s.total.p99),
+        s.total.reps,
+        memory,
+        flags.join(", ")
+    );
+    println!("{}", line.trim_end());
+}
+
+// Sums of the median runtimes
+pub fn print_totals(label: &str, t: &Timings) {
+    println!(
+        "{:8} {:10.1} {:10.1} {:10.1} | {:10.1}",
+        label,
+        us(t.parse),
+        us(t.part1),
+        us(t.part2),
+        us(t.total())
+    );
+}
+
+// Prints any part which is not known to be correct
+pub fn print_checks(answer: &Answers, checks: (Check, Check)) {
+    for (part, actual, check) in [(1, &answer.part1, checks.0), (2, &answer.part2, checks.1)] {
+        if check != Check::Pass && check != Check::Skipped {
+            println!("        part {}: {} ({})", part, check, actual);
+        }
+    }
+}
+
+pub fn print_summary(results: &[DayResult], failures: &[(String, String)]) {
+    let count = |c: Check| {
+        results
+            .iter()
+            .map(|r| (r.part1_check == c) as usize + (r.part2_check == c) as usize)
+            .sum::<usize>()
+    };
+    let skipped = match count(Check::Skipped) {
+        0 => String::new(),
+        n => format!(", {} skipped", n),
+    };
+    println!(
+        "Answers: {} passed, {} failed, {} unknown{}",
+        count(Check::Pass),
+        count(Check::Fail),
+        count(Check::Unknown),
+        skipped
+    );
+    for (name, failure) in failures {
+        println!("Day {}: {}", name, failure);
+    }
+}
+
+// Any failed answers or puzzles which did not complete are reflected in the
+// exit code
+pub fn exit_code(results: &[DayResult], failures: &[(String, String)]) -> i32 {
+    let wrong = results
+        .iter()
+        .any(|r| r.part1_check == Check::Fail || r.part2_check == Check::Fail);
+    if wrong || !failures.is_empty() {
+        1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(day: u8, median_ns: u64) -> DayResult {
+        DayResult {
+            year: 2022,
+            day,
+            part1: String::new(),
+            part2: String::new(),
+            part1_check: Check::Unknown,
+            part2_check: Check::Unknown,
+            parse_ns: 0,
+            part1_ns: 0,
+            part2_ns: 0,
+            median_ns,
+            mean_ns: median_ns,
+            min_ns: median_ns,
+            max_ns: median_ns,
+            stddev_ns: 0,
+            p90_ns: median_ns,
+            p99_ns: median_ns,
+            reps: 1,
+            outliers: 0,
+            converged: true,
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn compare_baseline() {
+        let baseline = vec![result(1, 1000), result(2, 1000)];
+        let results = vec![result(1, 1200), result(2, 1020), result(3, 1000)];
+        let (text, regressions) = compare(&baseline, &results, 5.0);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(1, regressions);
+        assert!(lines[0].ends_with("REGRESSION"));
+        assert!(lines[1].ends_with("+2.0%"));
+        assert_eq!("Day 03: not in baseline", lines[2]);
+    }
+}
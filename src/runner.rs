@@ -0,0 +1,352 @@
+use crate::alloc::{self, MemStats};
+use crate::answers::AnswerDb;
+use crate::error::{ParseError, ParseResult};
+use crate::registry::{self, Puzzle};
+use crate::report::{self, DayResult, Format};
+use crate::select;
+use crate::solution::{Answers, Parts, SolveFn, Timings};
+use crate::stats::{PuzzleStats, Stats};
+use crate::watchdog::{self, Failure, Heartbeat};
+use clap::Args;
+use rayon::prelude::*;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+// Don't trust the error estimate until we have at least this many samples
+const MIN_REPS: usize = 5;
+
+// Controls how long each puzzle is benchmarked
+#[derive(Args, Clone, Copy)]
+pub struct Limits {
+    #[arg(short = 'r', long, default_value_t = 1000)]
+    pub max_reps: usize,
+
+    #[arg(short = 's', long, default_value_t = 5)]
+    pub max_secs: u64,
+
+    /// Number of untimed runs before measuring
+    #[arg(short = 'w', long, default_value_t = 1)]
+    pub warmup: usize,
+
+    /// Stop measuring once the relative standard error of the mean
+    /// total runtime is below this
+    #[arg(short = 'e', long, default_value_t = 0.02)]
+    pub target_error: f64,
+}
+
+// Where the inputs come from, when not the embedded ones
+#[derive(Args, Clone, Default)]
+pub struct Inputs {
+    /// Read inputs from DIR/inputNN.txt (DIR/YYYY/inputNN.txt for years
+    /// other than the default) instead of the embedded ones
+    #[arg(long, value_name = "DIR")]
+    pub input_dir: Option<PathBuf>,
+
+    /// Read the input for a single puzzle from FILE
+    #[arg(long, value_name = "FILE", conflicts_with = "input_dir")]
+    pub input: Option<PathBuf>,
+}
+
+impl Inputs {
+    // Returns Ok(None) if the embedded input should be used
+    pub fn load(&self, p: &Puzzle) -> Result<Option<Vec<u8>>, String> {
+        let path = if let Some(file) = &self.input {
+            file.clone()
+        } else if let Some(dir) = &self.input_dir {
+            dir.join(p.input_file())
+        } else {
+            return Ok(None);
+        };
+
+        std::fs::read(&path)
+            .map(Some)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))
+    }
+}
+
+pub type Benchmark = (Answers, Vec<Timings>, bool, Option<MemStats>);
+
+// Runs the puzzle until the runtime estimate is good enough, or we run out
+// of repetitions or time.
+pub fn benchmark(
+    solve: SolveFn,
+    input: &[u8],
+    parts: Parts,
+    limits: &Limits,
+    heartbeat: &Heartbeat<ParseResult<Benchmark>>,
+) -> ParseResult<Benchmark> {
+    // Memory usage is taken from a separate, untimed run
+    let (result, memory) = alloc::measure(|| solve(input, parts));
+    if memory.is_some() {
+        result?;
+        heartbeat.beat();
+    }
+
+    for _ in 0..limits.warmup {
+        solve(input, parts)?;
+        heartbeat.beat();
+    }
+
+    let mut runtimes: Vec<Timings> = vec![];
+    let mut totals: Vec<Duration> = vec![];
+    let start = Instant::now();
+
+    loop {
+        let (answer, timings) = solve(input, parts)?;
+        heartbeat.beat();
+        runtimes.push(timings);
+        totals.push(timings.total());
+
+        let converged =
+            totals.len() >= MIN_REPS && Stats::new(&totals).relative_error() <= limits.target_error;
+        if converged
+            || start.elapsed().as_secs() >= limits.max_secs
+            || runtimes.len() >= limits.max_reps
+        {
+            return Ok((answer, runtimes, converged, memory));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    Parse(ParseError),
+    Failed(Failure),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunError::Parse(err) => write!(f, "bad input: {}", err),
+            RunError::Failed(failure) => write!(f, "{}", failure),
+        }
+    }
+}
+
+// Benchmarks the puzzle on a separate thread, so that panics and runaway
+// puzzles do not take down the whole run
+pub fn run_isolated(
+    p: &Puzzle,
+    input: &[u8],
+    parts: Parts,
+    limits: &Limits,
+    timeout: Duration,
+) -> Result<Benchmark, RunError> {
+    let solve = p.solve;
+    let (year, day) = (p.year, p.day);
+    let input = input.to_vec();
+    let limits = *limits;
+    match watchdog::run_isolated(timeout, move |heartbeat| {
+        benchmark(solve, &input, parts, &limits, heartbeat)
+    }) {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(err)) => Err(RunError::Parse(err.in_day(year, day))),
+        Err(failure) => Err(RunError::Failed(failure)),
+    }
+}
+
+// Solves the puzzle once on a separate thread, without benchmarking
+pub fn solve_isolated(
+    p: &Puzzle,
+    input: &[u8],
+    parts: Parts,
+    timeout: Duration,
+) -> Result<(Answers, Timings), RunError> {
+    let solve = p.solve;
+    let (year, day) = (p.year, p.day);
+    let input = input.to_vec();
+    match watchdog::run_isolated(timeout, move |_| solve(&input, parts)) {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(err)) => Err(RunError::Parse(err.in_day(year, day))),
+        Err(failure) => Err(RunError::Failed(failure)),
+    }
+}
+
+// How `run_puzzles` runs the puzzles and reports on them
+pub struct Options {
+    pub limits: Limits,
+    pub timeout: Duration,
+    pub inputs: Inputs,
+    pub format: Format,
+    // Run the puzzles concurrently, on `jobs` threads (default: one per CPU)
+    pub parallel: bool,
+    pub jobs: Option<usize>,
+    // Add answers which are not known yet, and save them to `answers`
+    pub record: bool,
+    pub answers: PathBuf,
+}
+
+// The input used for a puzzle, along with its benchmark result
+type Outcome = Result<(Option<Vec<u8>>, Benchmark), String>;
+
+fn measure(p: &Puzzle, parts: Parts, options: &Options) -> Outcome {
+    let runtime_input = options.inputs.load(p)?;
+    let input = runtime_input.as_deref().unwrap_or(p.input);
+    match run_isolated(p, input, parts, &options.limits, options.timeout) {
+        Ok(result) => Ok((runtime_input, result)),
+        Err(failure) => Err(failure.to_string()),
+    }
+}
+
+// Measures all puzzles at once on a separate thread pool. The results are
+// returned in the original order.
+fn measure_parallel<'a>(
+    puzzles: &[(&'a Puzzle, Parts)],
+    options: &Options,
+) -> Vec<(&'a Puzzle, Parts, Outcome)> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs.unwrap_or(0))
+        .build()
+        .expect("failed to create thread pool");
+    pool.install(|| {
+        puzzles
+            .par_iter()
+            .map(|(p, parts)| (*p, *parts, measure(p, *parts, options)))
+            .collect()
+    })
+}
+
+// Benchmarks the puzzles, checks their answers and prints the results in
+// the requested format. Returns the results, and the puzzles which failed
+// along with why.
+pub fn run_puzzles(
+    puzzles: Vec<(&Puzzle, Parts)>,
+    options: &Options,
+    db: &mut AnswerDb,
+) -> (Vec<DayResult>, Vec<(String, String)>) {
+    let mut total = Timings::default();
+    let mut results = vec![];
+    let mut failures = vec![];
+    let mut recorded = 0;
+    let table = options.format == Format::Table;
+    let start = Instant::now();
+
+    // In sequential mode, each puzzle is measured when its result is about
+    // to be printed.
+    let outcomes: Box<dyn Iterator<Item = (&Puzzle, Parts, Outcome)>> = if options.parallel {
+        let threads = options.jobs.unwrap_or_else(rayon::current_num_threads);
+        let note = format!(
+            "Running {} puzzle(s) in parallel on {} thread(s). Timings are taken under \
+             contention and are not comparable to a sequential run.",
+            puzzles.len(),
+            threads
+        );
+        if table {
+            println!("{}", note);
+        } else {
+            eprintln!("{}", note);
+        }
+        Box::new(measure_parallel(&puzzles, options).into_iter())
+    } else {
+        Box::new(
+            puzzles
+                .into_iter()
+                .map(|(p, parts)| (p, parts, measure(p, parts, options))),
+        )
+    };
+
+    if table {
+        report::print_header();
+    }
+
+    for (p, parts, outcome) in outcomes {
+        let (runtime_input, (answer, runtimes, converged, mut memory)) = match outcome {
+            Ok(result) => result,
+            Err(err) => {
+                eprintln!("Day {}: {}", p.name(), err);
+                failures.push((p.name(), err));
+                continue;
+            }
+        };
+        let input = runtime_input.as_deref().unwrap_or(p.input);
+        // The allocation counters are shared by all puzzles running at once
+        if options.parallel {
+            memory = None;
+        }
+        let checks = db.check(p.year, p.day, input, &answer, parts);
+        if options.record && db.record(p.year, p.day, input, &answer, parts) {
+            recorded += 1;
+        }
+
+        let stats = PuzzleStats::new(&runtimes);
+        if table {
+            report::print_stats(&format!("Day {}:", p.name()), &stats, converged, memory);
+            report::print_checks(&answer, checks);
+        }
+        total += Timings {
+            parse: stats.parse.median,
+            part1: stats.part1.median,
+            part2: stats.part2.median,
+        };
+        results.push(DayResult::new(
+            p, &answer, checks, &stats, converged, memory,
+        ));
+    }
+
+    if recorded > 0 {
+        match db.save(&options.answers) {
+            Ok(()) => eprintln!(
+                "Recorded answers for {} puzzle(s) in {}",
+                recorded,
+                options.answers.display()
+            ),
+            Err(err) => eprintln!("{}", err),
+        }
+    }
+
+    match options.format {
+        Format::Table => {
+            if !results.is_empty() {
+                report::print_totals("Total:", &total);
+                report::print_totals("Average:", &(total / results.len() as u32));
+            }
+            report::print_summary(&results, &failures);
+            if options.parallel {
+                println!(
+                    "Wall-clock time: {:.1} ms",
+                    start.elapsed().as_secs_f64() * 1000.0
+                );
+            }
+        }
+        Format::Json => println!("{}", report::to_json(&results)),
+        Format::Csv => print!("{}", report::to_csv(&results)),
+        Format::Markdown => print!("{}", report::to_markdown(&results)),
+    }
+
+    (results, failures)
+}
+
+// Prints the answers for the input in `file` (stdin by default), without
+// timing or checking them. The puzzle is given up on after `timeout`.
+pub fn run_solve(
+    selector: &str,
+    year: u16,
+    file: Option<&Path>,
+    timeout: Duration,
+) -> Result<(), String> {
+    let (year, day, parts) = select::single_day(selector, year)?;
+    let registry = registry::registry();
+    let p = registry::find(&registry, year, day)?;
+    let input = match file {
+        Some(path) => std::fs::read(path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?,
+        None => {
+            let mut input = vec![];
+            io::stdin()
+                .read_to_end(&mut input)
+                .map_err(|err| format!("Failed to read stdin: {}", err))?;
+            input
+        }
+    };
+    let (answers, _) = solve_isolated(p, &input, parts, timeout)
+        .map_err(|err| format!("Day {}: {}", p.name(), err))?;
+    if parts.part1() {
+        println!("Part 1: {}", answers.part1);
+    }
+    if parts.part2() {
+        println!("Part 2: {}", answers.part2);
+    }
+    Ok(())
+}
@@ -0,0 +1,266 @@
+// Scaffolding for new puzzles. `new-year` adds a module for the days of a
+// year (src/yYYYY/mod.rs), and `new-day` adds a day with a skeleton
+// solution, an empty input and an empty example. Everything is registered
+// right away, so the runner shows the new days (without known answers) on
+// the next build.
+//
+// The days of the default year live directly in src/, with their inputs in
+// inputs/.
+
+use crate::registry::DEFAULT_YEAR;
+use std::path::{Path, PathBuf};
+
+const DAY_MARKER: &str = "// New days are added above this line";
+const YEAR_MARKER: &str = "// New years are added above this line";
+
+const DAY_TEMPLATE: &str = "\
+use crate::error::{self, ParseResult};
+use crate::solution::Solution;
+
+pub struct DayNN;
+
+impl Solution for DayNN {
+    type Input = Vec<String>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(buf: &[u8]) -> ParseResult<Vec<String>> {
+        let input = error::as_str(buf)?;
+        Ok(input.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn part1(_lines: &Vec<String>) -> i64 {
+        0
+    }
+
+    fn part2(_lines: &Vec<String>) -> i64 {
+        0
+    }
+}
+";
+
+const YEAR_TEMPLATE: &str = "\
+// Advent of Code YYYY
+
+use crate::registry::Puzzle;
+
+pub fn puzzles() -> Vec<Puzzle> {
+    vec![
+        // New days are added above this line
+    ]
+}
+";
+
+// Where the files of a year go
+struct Layout {
+    src: PathBuf,
+    // The file with the `mod` declarations of the days
+    modules: PathBuf,
+    // The file with the list of puzzles
+    puzzles: PathBuf,
+    inputs: PathBuf,
+    // The inputs directory, relative to `src`
+    include: String,
+}
+
+fn layout(root: &Path, year: u16) -> Layout {
+    if year == DEFAULT_YEAR {
+        Layout {
+            src: root.join("src"),
+            modules: root.join("src/lib.rs"),
+            puzzles: root.join("src/registry.rs"),
+            inputs: root.join("inputs"),
+            include: "../inputs".to_string(),
+        }
+    } else {
+        let src = root.join(format!("src/y{}", year));
+        Layout {
+            modules: src.join("mod.rs"),
+            puzzles: src.join("mod.rs"),
+            src,
+            inputs: root.join(format!("inputs/{}", year)),
+            include: format!("../../inputs/{}", year),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))
+}
+
+fn write(path: &Path, text: &str) -> Result<(), String> {
+    std::fs::write(path, text).map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+// Writes a file which must not exist already
+fn create(path: &Path, text: &str) -> Result<(), String> {
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+    }
+    write(path, text)
+}
+
+// Inserts `line` before the marker, with the same indentation
+fn insert_before_marker(path: &Path, marker: &str, line: &str) -> Result<(), String> {
+    let text = read(path)?;
+    let at = text
+        .find(marker)
+        .ok_or_else(|| format!("{} has no '{}' line", path.display(), marker))?;
+    let start = text[..at].rfind('\n').map_or(0, |i| i + 1);
+    let indent = &text[start..at];
+    let text = format!("{}{}{}\n{}", &text[..start], indent, line, &text[start..]);
+    write(path, &text)
+}
+
+// Adds `pub mod name;` to the other module declarations, in order
+fn add_module(path: &Path, name: &str) -> Result<(), String> {
+    let text = read(path)?;
+    let decl = format!("pub mod {};", name);
+    let mut lines: Vec<&str> = text.lines().collect();
+    let modules: Vec<usize> = (0..lines.len())
+        .filter(|i| lines[*i].starts_with("pub mod "))
+        .collect();
+    let at = match modules.iter().find(|i| lines[**i] > decl.as_str()) {
+        Some(i) => *i,
+        None => match modules.last() {
+            Some(i) => i + 1,
+            // The first module goes after the leading comments
+            None => {
+                let i = lines
+                    .iter()
+                    .position(|line| !line.starts_with("//") && !line.is_empty())
+                    .unwrap_or(lines.len());
+                lines.insert(i, "");
+                i
+            }
+        },
+    };
+    lines.insert(at, &decl);
+    write(path, &(lines.join("\n") + "\n"))
+}
+
+// Returns the files created or changed
+pub fn new_year(root: &Path, year: u16) -> Result<Vec<PathBuf>, String> {
+    if !(2015..=2099).contains(&year) {
+        return Err(format!("{} is not an Advent of Code year", year));
+    }
+    let layout = layout(root, year);
+    if year == DEFAULT_YEAR {
+        return Err(format!(
+            "The {} puzzles live in src/ and inputs/ already",
+            year
+        ));
+    }
+    create(
+        &layout.modules,
+        &YEAR_TEMPLATE.replace("YYYY", &year.to_string()),
+    )?;
+    std::fs::create_dir_all(&layout.inputs)
+        .map_err(|err| format!("Failed to create {}: {}", layout.inputs.display(), err))?;
+
+    let module = format!("y{}", year);
+    let lib = root.join("src/lib.rs");
+    let registry = root.join("src/registry.rs");
+    add_module(&lib, &module)?;
+    insert_before_marker(
+        &registry,
+        YEAR_MARKER,
+        &format!(".chain({}::puzzles())", module),
+    )?;
+    Ok(vec![layout.modules, layout.inputs, lib, registry])
+}
+
+// Returns the files created or changed
+pub fn new_day(root: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("There is no day {}", day));
+    }
+    let layout = layout(root, year);
+    if !layout.modules.exists() {
+        return Err(format!(
+            "There is no {} (run new-year {} first)",
+            layout.modules.display(),
+            year
+        ));
+    }
+
+    let name = format!("{:02}", day);
+    let source = layout.src.join(format!("day{}.rs", name));
+    let input = layout.inputs.join(format!("input{}.txt", name));
+    let example = layout.inputs.join(format!("example{}.txt", name));
+    for path in [&source, &input, &example] {
+        if path.exists() {
+            return Err(format!("{} already exists", path.display()));
+        }
+    }
+
+    create(&source, &DAY_TEMPLATE.replace("NN", &name))?;
+    create(&input, "")?;
+    create(&example, "")?;
+    add_module(&layout.modules, &format!("day{}", name))?;
+    insert_before_marker(
+        &layout.puzzles,
+        DAY_MARKER,
+        &format!(
+            "Puzzle::make::<day{0}::Day{0}>({1}, {2}, include_bytes!(\"{3}/input{0}.txt\")),",
+            name, year, day, layout.include
+        ),
+    )?;
+    let mut paths = vec![source, input, example, layout.modules];
+    if layout.puzzles != paths[3] {
+        paths.push(layout.puzzles);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaffold() {
+        // Work on a copy of the real lib.rs and registry.rs
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        for file in ["src/lib.rs", "src/registry.rs"] {
+            std::fs::copy(file, root.join(file)).unwrap();
+        }
+
+        assert!(new_day(&root, 2023, 1)
+            .unwrap_err()
+            .contains("new-year 2023"));
+        assert_eq!(4, new_year(&root, 2023).unwrap().len());
+        assert!(new_year(&root, 2023).is_err());
+        new_day(&root, 2023, 7).unwrap();
+        new_day(&root, 2023, 1).unwrap();
+        assert!(new_day(&root, 2023, 1)
+            .unwrap_err()
+            .contains("already exists"));
+        assert!(new_day(&root, 2023, 26).is_err());
+        assert!(new_day(Path::new("."), 2022, 5)
+            .unwrap_err()
+            .contains("already exists"));
+
+        let lib = read(&root.join("src/lib.rs")).unwrap();
+        assert!(lib.contains("pub mod watchdog;\npub mod y2023;\n"));
+        let registry = read(&root.join("src/registry.rs")).unwrap();
+        assert!(registry.contains("        .chain(y2023::puzzles())\n        // New years"));
+
+        let year = read(&root.join("src/y2023/mod.rs")).unwrap();
+        assert!(year.starts_with("// Advent of Code 2023\n\npub mod day01;\npub mod day07;\n\n"));
+        assert!(year.contains(
+            "        Puzzle::make::<day07::Day07>(2023, 7, include_bytes!(\"../../inputs/2023/input07.txt\")),\n"
+        ));
+        let day = read(&root.join("src/y2023/day07.rs")).unwrap();
+        assert!(day.contains("pub struct Day07;"));
+        assert!(root.join("inputs/2023/input07.txt").exists());
+        assert!(root.join("inputs/2023/example01.txt").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
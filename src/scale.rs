@@ -0,0 +1,204 @@
+// Scaling studies: runtimes of one day over inputs of increasing size, and
+// an empirical complexity exponent fitted to them.
+//
+// Inputs are read from DIR/dayNN/N.txt, where N is the size of the input.
+
+use crate::generate;
+use crate::registry;
+use crate::report::us;
+use crate::runner::{self, Limits};
+use crate::solution::Parts;
+use crate::stats::PuzzleStats;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub fn day_dir(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{:02}", day))
+}
+
+// The inputs for a day, ordered by size
+pub fn inputs(dir: &Path, day: u8) -> Result<Vec<(usize, PathBuf)>, String> {
+    let dir = day_dir(dir, day);
+    let entries = std::fs::read_dir(&dir)
+        .map_err(|err| format!("Failed to read {}: {}", dir.display(), err))?;
+    let mut inputs = vec![];
+    for entry in entries {
+        let path = entry.map_err(|err| err.to_string())?.path();
+        let size = path
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_suffix(".txt")?.parse().ok());
+        if let Some(size) = size {
+            inputs.push((size, path));
+        }
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+// Writes generated inputs of the given sizes, keeping any which exist
+pub fn generate_inputs(dir: &Path, day: u8, sizes: &[usize], seed: u64) -> Result<(), String> {
+    let dir = day_dir(dir, day);
+    std::fs::create_dir_all(&dir)
+        .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+    for size in sizes {
+        let path = dir.join(format!("{}.txt", size));
+        if path.exists() {
+            continue;
+        }
+        let input = generate::generate(day, Some(*size), seed)
+            .ok_or_else(|| format!("There is no generator for day {}", day))?;
+        std::fs::write(&path, input)
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+    }
+    Ok(())
+}
+
+// Median runtimes for one input size
+pub struct Point {
+    pub size: usize,
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+    pub total: Duration,
+}
+
+impl Point {
+    pub fn new(size: usize, s: &PuzzleStats) -> Point {
+        Point {
+            size,
+            parse: s.parse.median,
+            part1: s.part1.median,
+            part2: s.part2.median,
+            total: s.total.median,
+        }
+    }
+}
+
+// Least squares fit of log(time) = k * log(size) + c. Returns k, or None if
+// there are not enough distinct (non-zero) points.
+pub fn fit_exponent(points: &[(usize, Duration)]) -> Option<f64> {
+    let logs: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|(size, time)| *size > 0 && !time.is_zero())
+        .map(|(size, time)| ((*size as f64).ln(), (time.as_nanos() as f64).ln()))
+        .collect();
+    let n = logs.len() as f64;
+    let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    if logs.len() < 2 || sxx == 0.0 {
+        None
+    } else {
+        Some(sxy / sxx)
+    }
+}
+
+// Exponents for parse, part 1, part 2 and the total
+pub fn exponents(points: &[Point]) -> [Option<f64>; 4] {
+    let fit = |f: fn(&Point) -> Duration| {
+        fit_exponent(&points.iter().map(|p| (p.size, f(p))).collect::<Vec<_>>())
+    };
+    [
+        fit(|p| p.parse),
+        fit(|p| p.part1),
+        fit(|p| p.part2),
+        fit(|p| p.total),
+    ]
+}
+
+pub fn to_csv(points: &[Point]) -> String {
+    let mut out = String::from("size,parse_ns,part1_ns,part2_ns,total_ns\n");
+    for p in points {
+        out.push_str(&format!(
+            "{},{},{},{},{}\n",
+            p.size,
+            p.parse.as_nanos(),
+            p.part1.as_nanos(),
+            p.part2.as_nanos(),
+            p.total.as_nanos()
+        ));
+    }
+    out
+}
+
+// Times DAY over the inputs in DIR, generating any missing ones of `sizes`
+// first, and writes the timings as CSV to `output` (stdout by default)
+pub fn run_scale(
+    day: u8,
+    dir: &Path,
+    sizes: &[usize],
+    seed: u64,
+    output: Option<&Path>,
+    limits: &Limits,
+    timeout: Duration,
+) -> Result<(), String> {
+    let registry = registry::registry();
+    // The inputs come from the generators
+    let p = registry::find(&registry, generate::YEAR, day)?;
+    if !sizes.is_empty() {
+        generate_inputs(dir, day, sizes, seed)?;
+    }
+    let inputs = inputs(dir, day)?;
+    if inputs.is_empty() {
+        return Err(format!("No inputs in {}", day_dir(dir, day).display()));
+    }
+
+    let mut points = vec![];
+    for (size, path) in inputs {
+        let input = std::fs::read(&path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        match runner::run_isolated(p, &input, Parts::Both, limits, timeout) {
+            Ok((_, runtimes, _, _)) => {
+                let point = Point::new(size, &PuzzleStats::new(&runtimes));
+                eprintln!("{:>10}: {:12.1} μs", size, us(point.total));
+                points.push(point);
+            }
+            // Larger inputs are not going to do any better
+            Err(err) => {
+                eprintln!("{:>10}: {}", size, err);
+                break;
+            }
+        }
+    }
+
+    let fmt = |k: Option<f64>| k.map_or("-".to_string(), |k| format!("{:.2}", k));
+    let [parse, part1, part2, total] = exponents(&points);
+    eprintln!(
+        "Exponents: parse {}, part 1 {}, part 2 {}, total {}",
+        fmt(parse),
+        fmt(part1),
+        fmt(part2),
+        fmt(total)
+    );
+
+    let csv = to_csv(&points);
+    match output {
+        None => print!("{}", csv),
+        Some(path) => std::fs::write(path, csv)
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponent() {
+        let quadratic: Vec<(usize, Duration)> = [10, 20, 40, 80]
+            .iter()
+            .map(|n| (*n, Duration::from_nanos(3 * (n * n) as u64)))
+            .collect();
+        assert!((fit_exponent(&quadratic).unwrap() - 2.0).abs() < 1e-9);
+
+        let linear = [
+            (100, Duration::from_micros(5)),
+            (1000, Duration::from_micros(50)),
+        ];
+        assert!((fit_exponent(&linear).unwrap() - 1.0).abs() < 1e-9);
+
+        assert_eq!(None, fit_exponent(&[(10, Duration::from_micros(5))]));
+    }
+}
@@ -0,0 +1,281 @@
+// Selection of puzzles on the command line. Each selector is a comma
+// separated list of terms:
+//
+//   7        day 7
+//   1-5      days 1 to 5
+//   15:2     only part 2 of day 15
+//   !19      everything except day 19
+//   2023:7   day 7 of 2023
+//   2023     all days of 2023
+//
+// Days without a year are from the default year. Without any included days,
+// all days of all years are selected before exclusions are applied.
+
+use crate::registry::{self, Puzzle};
+use crate::solution::Parts;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+// Some or all days of a year
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Days {
+    pub year: u16,
+    // None for the whole year
+    pub range: Option<RangeInclusive<u8>>,
+}
+
+impl Days {
+    pub fn contains(&self, year: u16, day: u8) -> bool {
+        self.year == year && self.range.as_ref().is_none_or(|r| r.contains(&day))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Include(Days, Parts),
+    Exclude(Days),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Speed {
+    Slow,
+    Fast,
+}
+
+fn day(s: &str, term: &str) -> Result<u8, String> {
+    s.parse()
+        .map_err(|_| format!("invalid selector '{}': '{}' is not a day", term, s))
+}
+
+fn days(s: &str, term: &str) -> Result<RangeInclusive<u8>, String> {
+    let range = match s.split_once('-') {
+        Some((first, last)) => day(first, term)?..=day(last, term)?,
+        None => day(s, term)?..=day(s, term)?,
+    };
+    if range.is_empty() {
+        return Err(format!("invalid selector '{}': empty range", term));
+    }
+    Ok(range)
+}
+
+// Days without a year are from `year`
+pub fn parse_term(term: &str, year: u16) -> Result<Term, String> {
+    let (exclude, rest) = match term.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, term),
+    };
+    let mut fields: Vec<&str> = rest.split(':').collect();
+
+    // A leading number which is too large to be a day is a year
+    let year = match fields[0].parse::<u16>() {
+        Ok(year) if year > 25 => {
+            if year < 2015 {
+                return Err(format!(
+                    "invalid selector '{}': {} is neither a day nor a year",
+                    term, year
+                ));
+            }
+            fields.remove(0);
+            year
+        }
+        _ => year,
+    };
+
+    let (range, parts) = match fields[..] {
+        [] => (None, Parts::Both),
+        [range] => (Some(range), Parts::Both),
+        [range, "1"] => (Some(range), Parts::Part1),
+        [range, "2"] => (Some(range), Parts::Part2),
+        [_, part] => {
+            return Err(format!(
+                "invalid selector '{}': '{}' is not a part (1 or 2)",
+                term, part
+            ))
+        }
+        _ => return Err(format!("invalid selector '{}': too many ':'", term)),
+    };
+    let days = Days {
+        year,
+        range: range.map(|range| days(range, term)).transpose()?,
+    };
+    match (exclude, parts) {
+        (false, _) => Ok(Term::Include(days, parts)),
+        (true, Parts::Both) => Ok(Term::Exclude(days)),
+        (true, _) => Err(format!(
+            "invalid selector '{}': parts cannot be excluded",
+            term
+        )),
+    }
+}
+
+// The day and parts of a term which names exactly one day
+pub fn single_day(term: &str, year: u16) -> Result<(u16, u8, Parts), String> {
+    match parse_term(term, year)? {
+        Term::Include(
+            Days {
+                year,
+                range: Some(days),
+            },
+            parts,
+        ) if days.start() == days.end() => Ok((year, *days.start(), parts)),
+        _ => Err(format!("'{}' is not a single day", term)),
+    }
+}
+
+// Picks puzzles (and their parts) from `puzzles` in order. Days and years
+// which are named explicitly must exist.
+pub fn select<'a>(
+    selectors: &[String],
+    puzzles: &[&'a Puzzle],
+    speed: Option<Speed>,
+    year: u16,
+) -> Result<Vec<(&'a Puzzle, Parts)>, String> {
+    let terms = selectors
+        .iter()
+        .flat_map(|s| s.split(','))
+        .map(|term| parse_term(term, year))
+        .collect::<Result<Vec<Term>, String>>()?;
+
+    for term in &terms {
+        let (Term::Include(days, _) | Term::Exclude(days)) = term;
+        match &days.range {
+            None if !puzzles.iter().any(|p| p.year == days.year) => {
+                return Err(format!("no such year: {}", days.year));
+            }
+            None => {}
+            Some(range) => {
+                for day in [range.start(), range.end()] {
+                    if !puzzles.iter().any(|p| p.year == days.year && p.day == *day) {
+                        return Err(format!("no such day: {}", registry::name(days.year, *day)));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut selected: BTreeMap<(u16, u8), Parts> = BTreeMap::new();
+    for term in &terms {
+        if let Term::Include(days, parts) = term {
+            for p in puzzles.iter().filter(|p| days.contains(p.year, p.day)) {
+                let key = (p.year, p.day);
+                let parts = selected.get(&key).map_or(*parts, |p| p.union(*parts));
+                selected.insert(key, parts);
+            }
+        }
+    }
+    if selected.is_empty() {
+        selected = puzzles
+            .iter()
+            .map(|p| ((p.year, p.day), Parts::Both))
+            .collect();
+    }
+    for term in &terms {
+        if let Term::Exclude(days) = term {
+            selected.retain(|(year, day), _| !days.contains(*year, *day));
+        }
+    }
+
+    Ok(puzzles
+        .iter()
+        .filter(|p| match speed {
+            Some(Speed::Slow) => p.slow,
+            Some(Speed::Fast) => !p.slow,
+            None => true,
+        })
+        .filter_map(|p| selected.get(&(p.year, p.day)).map(|parts| (*p, *parts)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::registry;
+
+    fn days(selectors: &[&str], speed: Option<Speed>) -> Result<Vec<(u8, Parts)>, String> {
+        let registry = registry();
+        let puzzles: Vec<&Puzzle> = registry.values().collect();
+        let selectors: Vec<String> = selectors.iter().map(|s| s.to_string()).collect();
+        Ok(select(&selectors, &puzzles, speed, 2022)?
+            .into_iter()
+            .map(|(p, parts)| (p.day, parts))
+            .collect())
+    }
+
+    fn range(year: u16, range: RangeInclusive<u8>) -> Days {
+        Days {
+            year,
+            range: Some(range),
+        }
+    }
+
+    #[test]
+    fn terms() {
+        let term = |s: &str| parse_term(s, 2022);
+        assert_eq!(
+            Ok(Term::Include(range(2022, 1..=5), Parts::Both)),
+            term("1-5")
+        );
+        assert_eq!(
+            Ok(Term::Include(range(2022, 15..=15), Parts::Part2)),
+            term("15:2")
+        );
+        assert_eq!(Ok(Term::Exclude(range(2022, 19..=19))), term("!19"));
+        assert!(term("5-1").is_err());
+        assert!(term("3:4").is_err());
+        assert!(term("x").is_err());
+
+        assert_eq!(
+            Ok(Term::Include(range(2023, 7..=7), Parts::Both)),
+            term("2023:7")
+        );
+        assert_eq!(
+            Ok(Term::Include(range(2022, 15..=15), Parts::Part1)),
+            term("2022:15:1")
+        );
+        let whole = Days {
+            year: 2023,
+            range: None,
+        };
+        assert_eq!(Ok(Term::Exclude(whole.clone())), term("!2023"));
+        assert_eq!(
+            Ok(Term::Include(range(2023, 3..=3), Parts::Both)),
+            parse_term("3", 2023)
+        );
+        assert!(term("1999:1").is_err());
+        assert!(term("!15:2").is_err());
+        assert!(term("2022:1:2:3").is_err());
+
+        assert_eq!(Ok((2023, 4, Parts::Part2)), single_day("2023:4:2", 2022));
+        assert_eq!(Ok((2022, 4, Parts::Both)), single_day("4", 2022));
+        assert!(single_day("4-5", 2022).is_err());
+        assert!(single_day("!4", 2022).is_err());
+    }
+
+    #[test]
+    fn selection() {
+        let both = |days: &[u8]| days.iter().map(|d| (*d, Parts::Both)).collect::<Vec<_>>();
+        assert_eq!(Ok(both(&[3, 4])), days(&["2022:3-4"], None));
+        assert_eq!(25, days(&["2022"], None).unwrap().len());
+        assert_eq!(Ok(vec![]), days(&["!2022"], None));
+        assert_eq!(Err("no such year: 2023".to_string()), days(&["2023"], None));
+        assert_eq!(
+            Err("no such day: 2023-01".to_string()),
+            days(&["2023:1"], None)
+        );
+        assert_eq!(Ok(both(&[1])), days(&["1"], None));
+        assert_eq!(Ok(both(&[7, 9, 12])), days(&["12,7", "9"], None));
+        assert_eq!(Ok(both(&[1, 2, 4, 5])), days(&["1-5", "!3"], None));
+        assert_eq!(Ok(vec![(15, Parts::Part2)]), days(&["15:2"], None));
+        assert_eq!(Ok(both(&[15])), days(&["15:2", "15:1"], None));
+        assert_eq!(24, days(&["!19"], None).unwrap().len());
+        assert_eq!(
+            Ok(both(&[16, 18, 19, 20])),
+            days(&["15-20"], Some(Speed::Slow))
+        );
+        assert!(days(&[], Some(Speed::Fast))
+            .unwrap()
+            .iter()
+            .all(|(d, _)| *d != 23));
+        assert_eq!(Err("no such day: 26".to_string()), days(&["20-26"], None));
+    }
+}
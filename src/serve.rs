@@ -0,0 +1,237 @@
+// Solving puzzles over HTTP, for tools which want answers without running
+// the binary themselves. Only enabled with the `serve` feature, and only
+// bound to localhost.
+//
+//   GET  /days        the registered days
+//   POST /solve/DAY   answers and timings for the input in the body
+//
+// DAY is a selector for a single day, such as 15, 15:2 or 2023:15. Every
+// solve runs isolated with a timeout. A thread which times out cannot be
+// stopped, so it keeps counting against the number of solves which may run
+// at once until it finishes, and requests beyond that are turned away.
+
+use crate::registry::{self, Registry};
+use crate::runner::RunError;
+use crate::select;
+use crate::watchdog::{self, Failure};
+use serde::Serialize;
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// Larger than any real input, but small enough to keep in memory
+const MAX_INPUT: usize = 16 * 1024 * 1024;
+
+#[derive(Serialize)]
+struct Day {
+    year: u16,
+    day: u8,
+    slow: bool,
+}
+
+#[derive(Serialize)]
+struct SolveTimings {
+    parse_ns: u64,
+    part1_ns: u64,
+    part2_ns: u64,
+    total_ns: u64,
+}
+
+// Parts which were not asked for are null
+#[derive(Serialize)]
+struct Solved {
+    year: u16,
+    day: u8,
+    part1: Option<String>,
+    part2: Option<String>,
+    timings: SolveTimings,
+}
+
+#[derive(Serialize)]
+struct Error {
+    error: String,
+}
+
+pub struct State {
+    registry: Registry,
+    timeout: Duration,
+    jobs: usize,
+    // Solves which have not finished, including those which timed out
+    running: Arc<AtomicUsize>,
+}
+
+impl State {
+    pub fn new(timeout: Duration, jobs: usize) -> State {
+        State {
+            registry: registry::registry(),
+            timeout,
+            jobs,
+            running: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+// Counts a solve as running for as long as its thread lives
+struct Running(Arc<AtomicUsize>);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn json(status: u16, body: &impl Serialize) -> (u16, String) {
+    (status, serde_json::to_string(body).unwrap())
+}
+
+fn error(status: u16, error: impl ToString) -> (u16, String) {
+    json(
+        status,
+        &Error {
+            error: error.to_string(),
+        },
+    )
+}
+
+fn solve(state: &State, selector: &str, input: Vec<u8>) -> (u16, String) {
+    let (year, day, parts) = match select::single_day(selector, registry::DEFAULT_YEAR) {
+        Ok(day) => day,
+        Err(err) => return error(404, err),
+    };
+    let Some(p) = state.registry.get(&(year, day)) else {
+        return error(404, format!("no such day: {}", registry::name(year, day)));
+    };
+
+    let running = state.running.fetch_add(1, Ordering::SeqCst);
+    let guard = Running(state.running.clone());
+    if running >= state.jobs {
+        return error(503, format!("busy, {} puzzles are still running", running));
+    }
+    let solve = p.solve;
+    let result = watchdog::run_isolated(state.timeout, move |_| {
+        let _guard = guard;
+        solve(&input, parts)
+    });
+    let (answers, timings) = match result {
+        Ok(Ok(solved)) => solved,
+        Ok(Err(err)) => return error(400, RunError::Parse(err.in_day(year, day))),
+        Err(failure @ Failure::TimedOut(_)) => return error(504, failure),
+        Err(failure) => return error(500, failure),
+    };
+    json(
+        200,
+        &Solved {
+            year,
+            day,
+            part1: parts.part1().then_some(answers.part1),
+            part2: parts.part2().then_some(answers.part2),
+            timings: SolveTimings {
+                parse_ns: timings.parse.as_nanos() as u64,
+                part1_ns: timings.part1.as_nanos() as u64,
+                part2_ns: timings.part2.as_nanos() as u64,
+                total_ns: timings.total().as_nanos() as u64,
+            },
+        },
+    )
+}
+
+// The status and JSON body of the response to one request
+pub fn handle(state: &State, method: &Method, url: &str, input: Vec<u8>) -> (u16, String) {
+    let path = url.split('?').next().unwrap_or_default();
+    match (method, path.strip_prefix("/solve/")) {
+        (Method::Post, Some(selector)) => solve(state, selector, input),
+        (_, Some(_)) => error(405, "use POST with the input as the body"),
+        (Method::Get, None) if path == "/days" => {
+            let days: Vec<Day> = state
+                .registry
+                .values()
+                .map(|p| Day {
+                    year: p.year,
+                    day: p.day,
+                    slow: p.slow,
+                })
+                .collect();
+            json(200, &days)
+        }
+        _ => error(404, format!("no such endpoint: {} {}", method, path)),
+    }
+}
+
+fn respond(state: &State, mut request: Request) {
+    let mut input = vec![];
+    let read = request
+        .as_reader()
+        .take(MAX_INPUT as u64 + 1)
+        .read_to_end(&mut input);
+    let (status, body) = match read {
+        Err(err) => error(400, format!("failed to read the input: {}", err)),
+        Ok(_) if input.len() > MAX_INPUT => error(413, "the input is too large"),
+        Ok(_) => handle(state, request.method(), request.url(), input),
+    };
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(content_type);
+    let _ = request.respond(response);
+}
+
+// Serves requests until the process is stopped. Each request gets a thread
+// of its own, so that slow puzzles do not hold up the others.
+pub fn serve(port: u16, state: State) -> Result<(), String> {
+    let address = format!("127.0.0.1:{}", port);
+    let server = Server::http(&address)
+        .map_err(|err| format!("Failed to listen on {}: {}", address, err))?;
+    eprintln!("Listening on http://{}", address);
+    let state = Arc::new(state);
+    for request in server.incoming_requests() {
+        let state = state.clone();
+        thread::spawn(move || respond(&state, request));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(state: &State, url: &str, input: &[u8]) -> (u16, serde_json::Value) {
+        let (status, body) = handle(state, &Method::Post, url, input.to_vec());
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    #[test]
+    fn requests() {
+        let state = State::new(Duration::from_secs(10), 1);
+        let example = std::fs::read("inputs/example01.txt").unwrap();
+
+        let (status, body) = post(&state, "/solve/1", &example);
+        assert_eq!(200, status);
+        assert_eq!("24000", body["part1"]);
+        assert_eq!("45000", body["part2"]);
+        assert!(body["timings"]["total_ns"].is_u64());
+
+        let (status, body) = post(&state, "/solve/2022:1:2", &example);
+        assert_eq!(200, status);
+        assert!(body["part1"].is_null());
+        assert_eq!("45000", body["part2"]);
+
+        assert_eq!(400, post(&state, "/solve/2", b"A Q\n").0);
+        assert_eq!(404, post(&state, "/solve/26", b"").0);
+        assert_eq!(404, post(&state, "/solve/1-3", b"").0);
+        assert_eq!(405, handle(&state, &Method::Get, "/solve/1", vec![]).0);
+
+        let (status, body) = handle(&state, &Method::Get, "/days", vec![]);
+        assert_eq!(200, status);
+        let days: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(25, days.as_array().unwrap().len());
+        assert_eq!(404, handle(&state, &Method::Get, "/", vec![]).0);
+
+        // Nothing may run at all
+        let busy = State::new(Duration::from_secs(10), 0);
+        assert_eq!(503, post(&busy, "/solve/1", &example).0);
+        assert_eq!(0, busy.running.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::error::ParseResult;
+use std::fmt::Display;
+use std::ops::{AddAssign, Div};
+use std::time::{Duration, Instant};
+
+// Common interface for all puzzle solutions. Parsing is kept separate from
+// the two parts so that the runner can call (and time) them individually.
+pub trait Solution {
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(buf: &[u8]) -> ParseResult<Self::Input>;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: String,
+    pub part2: String,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1 + self.part2
+    }
+}
+
+impl AddAssign for Timings {
+    fn add_assign(&mut self, other: Timings) {
+        self.parse += other.parse;
+        self.part1 += other.part1;
+        self.part2 += other.part2;
+    }
+}
+
+impl Div<u32> for Timings {
+    type Output = Timings;
+
+    fn div(self, n: u32) -> Timings {
+        Timings {
+            parse: self.parse / n,
+            part1: self.part1 / n,
+            part2: self.part2 / n,
+        }
+    }
+}
+
+// Which parts of a puzzle to run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Parts {
+    #[default]
+    Both,
+    Part1,
+    Part2,
+}
+
+impl Parts {
+    pub fn part1(self) -> bool {
+        self != Parts::Part2
+    }
+
+    pub fn part2(self) -> bool {
+        self != Parts::Part1
+    }
+
+    // The parts selected in either `self` or `other`
+    pub fn union(self, other: Parts) -> Parts {
+        if self == other {
+            self
+        } else {
+            Parts::Both
+        }
+    }
+}
+
+// The type-erased solver stored in the registry
+pub type SolveFn = fn(&[u8], Parts) -> ParseResult<(Answers, Timings)>;
+
+pub fn solve<S: Solution>(buf: &[u8]) -> ParseResult<(Answers, Timings)> {
+    solve_parts::<S>(buf, Parts::Both)
+}
+
+// Type-erased entry point, used by the registry. Parsing and the two parts
+// are timed separately. A part which is not run gets an empty answer.
+pub fn solve_parts<S: Solution>(buf: &[u8], parts: Parts) -> ParseResult<(Answers, Timings)> {
+    let t = Instant::now();
+    let input = S::parse(buf)?;
+    let parse = t.elapsed();
+
+    let t = Instant::now();
+    let p1 = parts.part1().then(|| S::part1(&input).to_string());
+    let part1 = t.elapsed();
+
+    let t = Instant::now();
+    let p2 = parts.part2().then(|| S::part2(&input).to_string());
+    let part2 = t.elapsed();
+
+    let answers = Answers {
+        part1: p1.unwrap_or_default(),
+        part2: p2.unwrap_or_default(),
+    };
+    Ok((
+        answers,
+        Timings {
+            parse,
+            part1,
+            part2,
+        },
+    ))
+}
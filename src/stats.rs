@@ -0,0 +1,115 @@
+use crate::solution::Timings;
+use std::time::Duration;
+
+// Summary statistics over a set of runtime samples
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub reps: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub stddev: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub outliers: usize,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty());
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        let mean = sorted.iter().sum::<Duration>() / n as u32;
+        let mean_ns = mean.as_nanos() as f64;
+        let variance = sorted
+            .iter()
+            .map(|d| (d.as_nanos() as f64 - mean_ns).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        // Tukey's fences: anything further than 1.5 IQR outside the
+        // interquartile range counts as an outlier.
+        let q1 = percentile(&sorted, 25.0).as_nanos() as f64;
+        let q3 = percentile(&sorted, 75.0).as_nanos() as f64;
+        let iqr = q3 - q1;
+        let outliers = sorted
+            .iter()
+            .map(|d| d.as_nanos() as f64)
+            .filter(|ns| *ns < q1 - 1.5 * iqr || *ns > q3 + 1.5 * iqr)
+            .count();
+
+        Stats {
+            reps: n,
+            mean,
+            median: percentile(&sorted, 50.0),
+            min: sorted[0],
+            max: sorted[n - 1],
+            stddev: Duration::from_nanos(variance.sqrt() as u64),
+            p90: percentile(&sorted, 90.0),
+            p99: percentile(&sorted, 99.0),
+            outliers,
+        }
+    }
+
+    // Standard error of the mean, relative to the mean
+    pub fn relative_error(&self) -> f64 {
+        let mean = self.mean.as_nanos() as f64;
+        if mean == 0.0 {
+            0.0
+        } else {
+            self.stddev.as_nanos() as f64 / (self.reps as f64).sqrt() / mean
+        }
+    }
+}
+
+// Statistics for each phase of a puzzle, and for the puzzle as a whole
+pub struct PuzzleStats {
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+    pub total: Stats,
+}
+
+impl PuzzleStats {
+    pub fn new(runtimes: &[Timings]) -> PuzzleStats {
+        let stats = |f: fn(&Timings) -> Duration| {
+            Stats::new(&runtimes.iter().map(f).collect::<Vec<Duration>>())
+        };
+        PuzzleStats {
+            parse: stats(|t| t.parse),
+            part1: stats(|t| t.part1),
+            part2: stats(|t| t.part2),
+            total: stats(|t| t.total()),
+        }
+    }
+}
+
+// Nearest-rank percentile of an already sorted slice
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.max(1) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stats;
+    use std::time::Duration;
+
+    #[test]
+    fn stats() {
+        let samples: Vec<Duration> = [10, 12, 11, 13, 10, 11, 12, 100]
+            .iter()
+            .map(|us| Duration::from_micros(*us))
+            .collect();
+        let stats = Stats::new(&samples);
+        assert_eq!(8, stats.reps);
+        assert_eq!(Duration::from_micros(11), stats.median);
+        assert_eq!(Duration::from_micros(10), stats.min);
+        assert_eq!(Duration::from_micros(100), stats.max);
+        assert_eq!(Duration::from_micros(100), stats.p99);
+        assert_eq!(1, stats.outliers);
+    }
+}
@@ -0,0 +1,388 @@
+// Frame by frame views of the puzzles which simulate a 2D world. A day opts
+// in by implementing `Visualize`. The frames can be played back in the
+// terminal, or written to a directory as text files.
+
+use crate::error::ParseResult;
+use crate::image::{self, Canvas, Style};
+use crate::registry;
+use crate::runner::Inputs;
+use crate::select;
+use crate::solution::{Parts, Solution};
+use crate::{day09, day14, day17, day22, day23, day24};
+use clap::Args;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+// One picture of the simulation, as rows of characters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub caption: String,
+    pub rows: Vec<String>,
+    // The cell (column, row) to keep in view when the frame is cropped
+    pub focus: Option<(usize, usize)>,
+}
+
+impl Frame {
+    // A frame of `width` x `height` cells, where `cell(col, row)` gives the
+    // character in each cell
+    pub fn draw(
+        caption: String,
+        width: usize,
+        height: usize,
+        cell: impl Fn(usize, usize) -> char,
+    ) -> Frame {
+        Frame {
+            caption,
+            rows: (0..height)
+                .map(|row| (0..width).map(|col| cell(col, row)).collect())
+                .collect(),
+            focus: None,
+        }
+    }
+
+    pub fn with_focus(mut self, col: usize, row: usize) -> Frame {
+        self.focus = Some((col, row));
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    // At most `width` x `height` cells around the focus (or the middle of
+    // the frame)
+    pub fn crop(&self, width: usize, height: usize) -> Vec<String> {
+        let (col, row) = self.focus.unwrap_or((self.width() / 2, self.height() / 2));
+        let start = |center: usize, size: usize, view: usize| {
+            center
+                .saturating_sub(view / 2)
+                .min(size.saturating_sub(view))
+        };
+        let first_col = start(col, self.width(), width);
+        let first_row = start(row, self.height(), height);
+        self.rows
+            .iter()
+            .skip(first_row)
+            .take(height)
+            .map(|row| row.chars().skip(first_col).take(width).collect())
+            .collect()
+    }
+}
+
+pub type Frames = Box<dyn Iterator<Item = Frame>>;
+
+pub trait Visualize: Solution {
+    // The frames of the simulation for part 1 or part 2 (`Parts::Both` is
+    // taken as part 1), or why there are none
+    fn frames(input: Self::Input, parts: Parts) -> Result<Frames, String>;
+}
+
+pub type FramesFn = fn(&[u8], Parts) -> ParseResult<Result<Frames, String>>;
+
+fn make<S: Visualize>(buf: &[u8], parts: Parts) -> ParseResult<Result<Frames, String>> {
+    Ok(S::frames(S::parse(buf)?, parts))
+}
+
+// The days which can be visualized, all from the same year
+pub const YEAR: u16 = 2022;
+pub const DAYS: [u8; 6] = [9, 14, 17, 22, 23, 24];
+
+// Parse errors are tagged with the year and day
+pub fn frames(year: u16, day: u8, input: &[u8], parts: Parts) -> Option<Result<Frames, String>> {
+    let make: FramesFn = match (year, day) {
+        (YEAR, 9) => make::<day09::Day09>,
+        (YEAR, 14) => make::<day14::Day14>,
+        (YEAR, 17) => make::<day17::Day17>,
+        (YEAR, 22) => make::<day22::Day22>,
+        (YEAR, 23) => make::<day23::Day23>,
+        (YEAR, 24) => make::<day24::Day24>,
+        _ => return None,
+    };
+    Some(
+        make(input, parts)
+            .map_err(|err| err.in_day(year, day).to_string())
+            .and_then(|frames| frames),
+    )
+}
+
+// Colors of the cells, shared by all days
+pub fn color(c: char) -> Option<[u8; 3]> {
+    match c {
+        '#' | '|' | '-' | '+' => Some([160, 160, 160]),
+        'o' => Some([230, 200, 80]),
+        '@' | 'E' | 'H' => Some([240, 60, 60]),
+        '<' | '>' | '^' | 'v' => Some([90, 200, 230]),
+        '0'..='9' | 'T' => Some([100, 220, 100]),
+        's' => Some([200, 120, 240]),
+        '.' | '~' => Some([80, 80, 80]),
+        _ => None,
+    }
+}
+
+// Controls for playing back frames in the terminal
+#[derive(Args, Clone, Copy)]
+pub struct Playback {
+    /// Frames per second for --visualize
+    #[arg(long, default_value_t = 10.0)]
+    pub fps: f64,
+
+    /// Start paused, showing one frame for each Enter
+    #[arg(long)]
+    pub step: bool,
+
+    /// Only show every Nth frame
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    pub every: usize,
+
+    /// Largest part of a frame to show, in cells
+    #[arg(long, value_name = "COLSxROWS", default_value = "100x40", value_parser = parse_view)]
+    pub view: (usize, usize),
+}
+
+// Where to write the frames instead of showing them, if anywhere
+#[derive(Args, Clone, Default)]
+pub struct Export {
+    /// Write the frames for --visualize to DIR as text files instead of
+    /// showing them
+    #[arg(long, value_name = "DIR", requires = "visualize", conflicts_with_all = ["png", "gif"])]
+    pub frames_dir: Option<PathBuf>,
+
+    /// Write the frames for --visualize to DIR as PNG images
+    #[arg(
+        long,
+        value_name = "DIR",
+        requires = "visualize",
+        conflicts_with = "gif"
+    )]
+    pub png: Option<PathBuf>,
+
+    /// Write the frames for --visualize to FILE as an animated GIF, at
+    /// --fps frames per second
+    #[arg(long, value_name = "FILE", requires = "visualize")]
+    pub gif: Option<PathBuf>,
+}
+
+fn parse_view(s: &str) -> Result<(usize, usize), String> {
+    s.split_once('x')
+        .and_then(|(cols, rows)| Some((cols.parse().ok()?, rows.parse().ok()?)))
+        .filter(|(cols, rows)| *cols > 0 && *rows > 0)
+        .ok_or_else(|| format!("expected COLSxROWS, e.g. 100x40, not '{}'", s))
+}
+
+const HELP: &str = "Enter: pause/step   p: play/pause   +/-: speed   q: quit";
+
+fn paint(out: &mut impl Write, rows: &[String]) -> io::Result<()> {
+    for row in rows {
+        let mut current = None;
+        for c in row.chars() {
+            let color = color(c);
+            if color != current {
+                match color {
+                    Some([r, g, b]) => write!(out, "\x1b[38;2;{};{};{}m", r, g, b)?,
+                    None => write!(out, "\x1b[0m")?,
+                }
+                current = color;
+            }
+            write!(out, "{}", c)?;
+        }
+        writeln!(out, "\x1b[0m")?;
+    }
+    Ok(())
+}
+
+// Lines typed on stdin. The channel is closed at end of input.
+fn commands() -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+// Shows the frames as ANSI text. Commands are read from stdin, one per line.
+pub fn play(title: &str, frames: Frames, playback: &Playback) -> io::Result<()> {
+    let commands = commands();
+    let mut delay = Duration::from_secs_f64(1.0 / playback.fps.max(0.01));
+    let mut paused = playback.step;
+    let (width, height) = playback.view;
+    let mut out = io::stdout().lock();
+
+    for (n, frame) in frames.step_by(playback.every.max(1)).enumerate() {
+        write!(out, "\x1b[H\x1b[2J")?;
+        writeln!(
+            out,
+            "{}  frame {}  {}",
+            title,
+            n * playback.every.max(1),
+            frame.caption
+        )?;
+        paint(&mut out, &frame.crop(width, height))?;
+        writeln!(
+            out,
+            "{:.1} fps{}   {}",
+            1.0 / delay.as_secs_f64(),
+            if paused { " (paused)" } else { "" },
+            HELP
+        )?;
+        out.flush()?;
+
+        loop {
+            let command = if paused {
+                match commands.recv() {
+                    Ok(command) => command,
+                    // Nobody can unpause us
+                    Err(_) => {
+                        paused = false;
+                        continue;
+                    }
+                }
+            } else {
+                match commands.recv_timeout(delay) {
+                    Ok(command) => command,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        thread::sleep(delay);
+                        break;
+                    }
+                }
+            };
+            match command.trim() {
+                "" if paused => break,
+                "" | "p" => paused = !paused,
+                "+" => delay /= 2,
+                "-" => delay *= 2,
+                "q" => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+// Writes every Nth frame to DIR/NNNNNN.txt, with the caption on the first
+// line. Returns the number of frames written.
+pub fn dump(frames: Frames, dir: &Path, every: usize) -> io::Result<usize> {
+    std::fs::create_dir_all(dir)?;
+    let mut count = 0;
+    for (n, frame) in frames.enumerate().step_by(every.max(1)) {
+        let mut text = format!("{}\n", frame.caption);
+        for row in &frame.rows {
+            text.push_str(row);
+            text.push('\n');
+        }
+        std::fs::write(dir.join(format!("{:06}.txt", n)), text)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+// Shows the frames of one day (DAY:2 for part 2), or writes them out as
+// `export` asks
+pub fn run_visualize(
+    selector: &str,
+    year: u16,
+    inputs: &Inputs,
+    export: &Export,
+    playback: &Playback,
+    style: &Style,
+) -> Result<(), String> {
+    let (year, day, parts) = select::single_day(selector, year)?;
+    let registry = registry::registry();
+    let p = registry::find(&registry, year, day)?;
+    let input = inputs.load(p)?;
+    let input = input.as_deref().unwrap_or(p.input);
+    // Images need two passes over the frames, so they are made on demand
+    let frames = || {
+        self::frames(year, day, input, parts).ok_or_else(|| {
+            format!(
+                "Day {} cannot be visualized, only days {:?} of {}",
+                p.name(),
+                DAYS,
+                YEAR
+            )
+        })?
+    };
+    let every = playback.every;
+
+    if let Some(dir) = &export.frames_dir {
+        let n = dump(frames()?, dir, every)
+            .map_err(|err| format!("Failed to write {}: {}", dir.display(), err))?;
+        eprintln!("Wrote {} frames to {}", n, dir.display());
+    } else if let Some(dir) = &export.png {
+        let canvas = Canvas::of(frames()?, every);
+        let n = image::write_pngs(frames()?, &canvas, dir, every, style)?;
+        eprintln!("Wrote {} images to {}", n, dir.display());
+    } else if let Some(path) = &export.gif {
+        let canvas = Canvas::of(frames()?, every);
+        let n = image::write_gif(frames()?, &canvas, path, every, playback.fps, style)?;
+        eprintln!("Wrote {} frames to {}", n, path.display());
+    } else {
+        let part = if parts == Parts::Part2 { 2 } else { 1 };
+        let title = format!("Day {} part {}", p.name(), part);
+        play(&title, frames()?, playback).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(day: u8, parts: Parts) -> Vec<Frame> {
+        let input = std::fs::read(format!("inputs/example{:02}.txt", day)).unwrap();
+        frames(YEAR, day, &input, parts).unwrap().unwrap().collect()
+    }
+
+    #[test]
+    fn crop() {
+        let frame = Frame::draw(String::new(), 10, 6, |col, row| {
+            char::from_digit(((col + row) % 10) as u32, 10).unwrap()
+        });
+        assert_eq!(vec!["345", "456"], frame.with_focus(3, 2).crop(3, 2));
+
+        let corner = Frame::draw(String::new(), 10, 6, |_, _| '.').with_focus(9, 5);
+        assert_eq!(vec!["...."; 3], corner.crop(4, 3));
+        assert_eq!(6, corner.crop(20, 20).len());
+    }
+
+    #[test]
+    fn examples() {
+        // One frame before the simulation starts, and one for each step
+        assert_eq!(25, example(14, Parts::Part1).len());
+        assert_eq!(94, example(14, Parts::Part2).len());
+        assert_eq!(19, example(24, Parts::Part1).len());
+        assert_eq!(55, example(24, Parts::Part2).len());
+        assert_eq!(11, example(23, Parts::Part1).len());
+        assert_eq!(21, example(23, Parts::Part2).len());
+        assert_eq!(2023, example(17, Parts::Part1).len());
+
+        let first = &example(14, Parts::Part1)[0];
+        assert!(first.rows.iter().any(|row| row.contains('+')));
+        assert!(!first.rows.iter().any(|row| row.contains('o')));
+
+        assert!(frames(YEAR, 1, b"", Parts::Part1).is_none());
+        assert!(frames(2023, 9, b"", Parts::Part1).is_none());
+        assert!(frames(YEAR, 9, b"X 1\n", Parts::Part1).unwrap().is_err());
+
+        // The way into the valley is walled up
+        let walled = b"#.###\n###.#\n###.#\n";
+        let err = frames(YEAR, 24, walled, Parts::Part1).unwrap().err();
+        assert_eq!(Some("there is no way from (1,0) to (3,2)".to_string()), err);
+    }
+}
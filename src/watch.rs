@@ -0,0 +1,245 @@
+// Re-running days while working on them. The sources and inputs of the days
+// are polled for changes, and on every change the runner is rebuilt with
+// cargo and run as a child process. Its results are shown next to those of
+// the previous run.
+
+use crate::answers::Check;
+use crate::registry::{self, Puzzle};
+use crate::report::DayResult;
+use crate::select;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+// The files of a day which are watched
+pub fn files(root: &Path, p: &Puzzle) -> [PathBuf; 2] {
+    [
+        root.join(p.source_file()),
+        root.join("inputs").join(p.input_file()),
+    ]
+}
+
+// Modification times, None for files which cannot be read
+pub type Snapshot = Vec<Option<SystemTime>>;
+
+pub fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    paths
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+// The runner is built with the same profile as this one
+fn profile() -> &'static str {
+    if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    }
+}
+
+// Builds the runner in `root`
+pub fn build(root: &Path) -> Result<(), String> {
+    let mut cargo = Command::new("cargo");
+    cargo.arg("build").current_dir(root);
+    if profile() == "release" {
+        cargo.arg("--release");
+    }
+    let output = cargo
+        .output()
+        .map_err(|err| format!("Failed to run cargo: {}", err))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
+
+// The runner which `build` makes, which need not be the one running (such
+// as when it was installed, or `root` is another checkout)
+pub fn runner(root: &Path) -> PathBuf {
+    root.join("target")
+        .join(profile())
+        .join(format!("aoc2022rust{}", std::env::consts::EXE_SUFFIX))
+}
+
+// Runs the (rebuilt) runner with `args`, which must ask for JSON output.
+// Returns the results and anything written to stderr, such as puzzles
+// which failed.
+pub fn run(exe: &Path, args: &[String]) -> Result<(Vec<DayResult>, String), String> {
+    let output = Command::new(exe)
+        .args(args)
+        .output()
+        .map_err(|err| format!("Failed to run {}: {}", exe.display(), err))?;
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    // Failed answers give an exit code, but the results are still there
+    match serde_json::from_slice(&output.stdout) {
+        Ok(results) => Ok((results, stderr)),
+        Err(_) if output.stdout.is_empty() => Ok((vec![], stderr)),
+        Err(err) => Err(format!("Unexpected output from {}: {}", exe.display(), err)),
+    }
+}
+
+fn answer(part: &str, check: Check, old: Option<&str>) -> String {
+    let check = match check {
+        Check::Pass | Check::Skipped => String::new(),
+        check => format!(" {}", check),
+    };
+    match old {
+        Some(old) if old != part => format!("{}{} (was {})", part, check, old),
+        _ => format!("{}{}", part, check),
+    }
+}
+
+// One line per day, with answers which changed since the previous run and
+// the change in the median runtime
+pub fn diff(previous: &[DayResult], current: &[DayResult]) -> String {
+    let mut out = String::new();
+    for r in current {
+        let old = previous.iter().find(|b| b.year == r.year && b.day == r.day);
+        let delta = match old {
+            Some(old) if old.median_ns > 0 => format!(
+                " {:+7.1}%",
+                (r.median_ns as f64 - old.median_ns as f64) / old.median_ns as f64 * 100.0
+            ),
+            _ => String::new(),
+        };
+        out.push_str(&format!(
+            "Day {}: {:10.1} μs{} | {} | {}\n",
+            registry::name(r.year, r.day),
+            r.median_ns as f64 / 1000.0,
+            delta,
+            answer(&r.part1, r.part1_check, old.map(|old| old.part1.as_str())),
+            answer(&r.part2, r.part2_check, old.map(|old| old.part2.as_str())),
+        ));
+    }
+    out
+}
+
+// The arguments for the runner, for the puzzles in `selectors`
+pub fn run_args(selectors: &[String], year: u16, timeout: Duration, answers: &Path) -> Vec<String> {
+    let mut args = vec![
+        format!("--year={}", year),
+        format!("--timeout={}", timeout.as_secs()),
+        format!("--answers={}", answers.display()),
+        "--format=json".to_string(),
+    ];
+    args.extend(selectors.iter().cloned());
+    args
+}
+
+// Rebuilds and re-runs the selected puzzles on every change, until killed
+pub fn run_watch(
+    selectors: &[String],
+    interval: Duration,
+    root: &Path,
+    year: u16,
+    timeout: Duration,
+    answers: &Path,
+) -> Result<(), String> {
+    let registry = registry::registry();
+    let puzzles: Vec<&Puzzle> = registry.values().collect();
+    let subset = select::select(selectors, &puzzles, None, year)?;
+    let files: Vec<PathBuf> = subset.iter().flat_map(|(p, _)| files(root, p)).collect();
+    let args = run_args(selectors, year, timeout, answers);
+
+    let exe = runner(root);
+    let mut seen = None;
+    let mut previous = vec![];
+    loop {
+        let now = snapshot(&files);
+        if seen.as_ref() == Some(&now) {
+            std::thread::sleep(interval);
+            continue;
+        }
+        seen = Some(now);
+
+        print!("\x1b[H\x1b[2J");
+        println!("Building...");
+        if let Err(err) = build(root) {
+            println!("{}", err);
+            continue;
+        }
+        println!("Running...");
+        let (results, stderr) = match run(&exe, &args) {
+            Ok(output) => output,
+            Err(err) => {
+                println!("{}", err);
+                continue;
+            }
+        };
+        print!("\x1b[H\x1b[2J");
+        print!("{}", diff(&previous, &results));
+        print!("{}", stderr);
+        println!("\nWatching {} files, press Ctrl-C to stop", files.len());
+        if !results.is_empty() {
+            previous = results;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(day: u8, part1: &str, median_ns: u64) -> DayResult {
+        DayResult {
+            year: 2022,
+            day,
+            part1: part1.to_string(),
+            part2: "2".to_string(),
+            part1_check: Check::Pass,
+            part2_check: Check::Unknown,
+            parse_ns: 0,
+            part1_ns: 0,
+            part2_ns: 0,
+            median_ns,
+            mean_ns: 0,
+            min_ns: 0,
+            max_ns: 0,
+            stddev_ns: 0,
+            p90_ns: 0,
+            p99_ns: 0,
+            reps: 1,
+            outliers: 0,
+            converged: true,
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn changes() {
+        let first = vec![result(1, "10", 2000)];
+        assert_eq!(
+            "Day 01:        2.0 μs | 10 | 2 UNKNOWN\n",
+            diff(&[], &first)
+        );
+        let second = vec![result(1, "11", 1500), result(2, "5", 1000)];
+        assert_eq!(
+            "Day 01:        1.5 μs   -25.0% | 11 (was 10) | 2 UNKNOWN\n\
+             Day 02:        1.0 μs | 5 | 2 UNKNOWN\n",
+            diff(&first, &second)
+        );
+    }
+
+    #[test]
+    fn watched_files() {
+        let registry = registry::registry();
+        let paths = files(Path::new("."), &registry[&(2022, 16)]);
+        assert_eq!(Path::new("./src/day16.rs"), paths[0]);
+        assert_eq!(Path::new("./inputs/input16.txt"), paths[1]);
+        let times = snapshot(&[paths[0].clone(), PathBuf::from("no/such/file")]);
+        assert!(times[0].is_some());
+        assert_eq!(None, times[1]);
+    }
+
+    #[test]
+    fn built_runner() {
+        let exe = runner(Path::new("root"));
+        assert_eq!(
+            Path::new("root/target").join(profile()),
+            exe.parent().unwrap()
+        );
+        assert_eq!("aoc2022rust", exe.file_stem().unwrap());
+    }
+}
@@ -0,0 +1,114 @@
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+// Same as the main thread, so that deeply recursive solutions behave the same
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Panicked(msg) => write!(f, "panicked: {}", msg),
+            Failure::TimedOut(timeout) => write!(f, "timed out after {:?}", timeout),
+        }
+    }
+}
+
+enum Msg<T> {
+    Alive,
+    Done(T),
+    Panicked(String),
+}
+
+// Handed to the isolated function, which should call `beat()` regularly to
+// show that it is still making progress.
+pub struct Heartbeat<T>(Sender<Msg<T>>);
+
+impl<T> Heartbeat<T> {
+    pub fn beat(&self) {
+        let _ = self.0.send(Msg::Alive);
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+// Runs `f` on a separate thread, catching panics. Fails if more than
+// `timeout` passes without a heartbeat or a result. There is no way to kill
+// a thread, so a timed out worker is left running in the background until
+// the process exits.
+pub fn run_isolated<T, F>(timeout: Duration, f: F) -> Result<T, Failure>
+where
+    T: Send + 'static,
+    F: FnOnce(&Heartbeat<T>) -> T + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let heartbeat = Heartbeat(tx);
+            let msg = match panic::catch_unwind(AssertUnwindSafe(|| f(&heartbeat))) {
+                Ok(value) => Msg::Done(value),
+                Err(payload) => Msg::Panicked(panic_message(payload)),
+            };
+            let _ = heartbeat.0.send(msg);
+        })
+        .expect("failed to spawn worker thread");
+
+    loop {
+        match rx.recv_timeout(timeout) {
+            Ok(Msg::Alive) => continue,
+            Ok(Msg::Done(value)) => return Ok(value),
+            Ok(Msg::Panicked(msg)) => return Err(Failure::Panicked(msg)),
+            Err(RecvTimeoutError::Timeout) => return Err(Failure::TimedOut(timeout)),
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(Failure::Panicked("worker thread died".to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isolation() {
+        let timeout = Duration::from_millis(200);
+        assert_eq!(Ok(42), run_isolated(timeout, |_| 42));
+        assert_eq!(
+            Err(Failure::Panicked("oops".to_string())),
+            run_isolated(timeout, |_| -> i32 { panic!("oops") })
+        );
+        assert_eq!(
+            Err(Failure::TimedOut(timeout)),
+            run_isolated(timeout, |_| thread::sleep(Duration::from_secs(2)))
+        );
+
+        // Heartbeats keep the watchdog happy
+        let slow = |hb: &Heartbeat<i32>| {
+            for _ in 0..5 {
+                thread::sleep(Duration::from_millis(100));
+                hb.beat();
+            }
+            7
+        };
+        assert_eq!(Ok(7), run_isolated(timeout, slow));
+    }
+}
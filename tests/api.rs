@@ -0,0 +1,342 @@
+use aoc2022rust::answers::{AnswerDb, Check};
+use aoc2022rust::crosscheck;
+use aoc2022rust::day13::{self, Packet};
+use aoc2022rust::day15;
+use aoc2022rust::image::Style;
+use aoc2022rust::registry::registry;
+use aoc2022rust::report::{self, Format};
+use aoc2022rust::runner::{self, Inputs, Limits, Options};
+use aoc2022rust::scale;
+use aoc2022rust::solution::{self, Parts, Solution};
+use aoc2022rust::visualize::{self, Export, Playback};
+use aoc2022rust::watch;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Days which are fast enough to run in debug builds
+const FAST_DAYS: [u8; 8] = [1, 2, 3, 4, 5, 6, 10, 13];
+
+#[test]
+fn embedded_inputs_match_answers() {
+    let db = AnswerDb::load(Path::new("answers.toml")).unwrap();
+    let registry = registry();
+    for day in FAST_DAYS {
+        let p = &registry[&(2022, day)];
+        let (answer, _) = p.run(p.input).unwrap();
+        assert_eq!(
+            (Check::Pass, Check::Pass),
+            db.check(2022, day, p.input, &answer, Parts::Both),
+            "Day {}",
+            day
+        );
+    }
+}
+
+#[test]
+fn solution_trait() {
+    let input =
+        day13::Day13::parse(b"[1,1,3,1,1]\n[1,1,5,1,1]\n\n[[1],[2,3,4]]\n[[1],4]\n").unwrap();
+    assert_eq!(2, input.len());
+    assert_eq!(3, day13::Day13::part1(&input));
+
+    let (answer, timings) = solution::solve::<day13::Day13>(b"[1]\n[2]\n").unwrap();
+    assert_eq!("1", answer.part1);
+    assert!(timings.total() >= timings.parse);
+}
+
+#[test]
+fn parse_errors() {
+    let registry = registry();
+    let err = registry[&(2022, 2)].run(b"A X\nB Q\n").unwrap_err();
+    assert_eq!((2, 2, 1), (err.day, err.line, err.column));
+
+    let err = registry[&(2022, 18)].run(b"1,2,3\n4,5\n").unwrap_err();
+    assert_eq!((18, 2, 4), (err.day, err.line, err.column));
+
+    // An odd number of packets
+    let err = registry[&(2022, 13)].run(b"[1]\n[2]\n\n[3]\n").unwrap_err();
+    assert_eq!((13, 4, 1), (err.day, err.line, err.column));
+    assert_eq!("packet without a pair", err.message);
+
+    // Truncated inputs are errors (or give an answer), but never panic
+    let day07 = &registry[&(2022, 7)];
+    for percent in [3, 10, 50, 99] {
+        let _ = day07.run(&day07.input[..day07.input.len() * percent / 100]);
+    }
+    let err = day07.run(b"$ cd /\n$ ls\ndir a\n12 b.txt\n").unwrap_err();
+    assert_eq!((7, 3, 1), (err.day, err.line, err.column));
+    assert_eq!("directory is never entered", err.message);
+
+    let err = registry[&(2022, 21)]
+        .run(b"root: pppw + sjmn\npppw: 2\n")
+        .unwrap_err();
+    assert_eq!((21, 1, 14), (err.day, err.line, err.column));
+    let err = registry[&(2022, 21)]
+        .run(b"root: pppw + sjmn\npppw: 2\nsjmn: 3\n")
+        .unwrap_err();
+    assert_eq!("no monkey named humn", err.message);
+
+    let err = registry[&(2022, 5)]
+        .run(b"    [D]\n[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 3 from 1 to 2\n")
+        .unwrap_err();
+    assert_eq!((5, 6, 6), (err.day, err.line, err.column));
+    assert_eq!("not that many crates on the stack", err.message);
+
+    let err = registry[&(2022, 3)].run(b"ab\ncd\nef\ngh\n").unwrap_err();
+    assert_eq!((3, 4, 1), (err.day, err.line, err.column));
+    assert_eq!("expected a group of three rucksacks", err.message);
+    let err = registry[&(2022, 3)].run(b"ab\ncde\nef\n").unwrap_err();
+    assert_eq!((3, 2, 1), (err.day, err.line, err.column));
+
+    let err = registry[&(2022, 6)]
+        .run(b"abcabcabcabcabcabc\n")
+        .unwrap_err();
+    assert_eq!("no start-of-packet marker", err.message);
+    let err = registry[&(2022, 6)]
+        .run(b"abcdabcdabcdabcdabcd\n")
+        .unwrap_err();
+    assert_eq!("no start-of-message marker", err.message);
+
+    let err = registry[&(2022, 23)].run(b"...\n...\n").unwrap_err();
+    assert_eq!("no elves", err.message);
+
+    let err = registry[&(2022, 24)].run(b"\n").unwrap_err();
+    assert_eq!("expected at least 3 rows and columns", err.message);
+    let err = registry[&(2022, 24)].run(b"#.#\n#.#\n###\n").unwrap_err();
+    assert_eq!((24, 3, 1), (err.day, err.line, err.column));
+}
+
+#[test]
+fn unreachable_valley() {
+    // The solver fails the same way as the visualizer does
+    let walled = b"#.###\n###.#\n###.#\n";
+    let registry = registry();
+    let p = &registry[&(2022, 24)];
+    let err = runner::solve_isolated(p, walled, Parts::Part1, Duration::from_secs(10));
+    assert_eq!(
+        "panicked: there is no way from (1,0) to (3,2)",
+        err.unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn day17_single_jet() {
+    // Cycles where the rocks left are a whole number of cycles
+    let registry = registry();
+    let p = &registry[&(2022, 17)];
+    let (answer, _) = p.run(b">\n").unwrap();
+    assert_eq!(
+        ("5256", "2600000000000"),
+        (&answer.part1[..], &answer.part2[..])
+    );
+    let (answer, _) = p.run(b"<\n").unwrap();
+    assert_eq!("2200000000000", answer.part2);
+}
+
+#[test]
+fn packets() {
+    // The parsed packet is wrapped in an outer list
+    let packet = day13::tokenize_and_parse("[1,[2]]");
+    assert_eq!(
+        Packet::LIST(vec![Packet::LIST(vec![
+            Packet::INT(1),
+            Packet::LIST(vec![Packet::INT(2)])
+        ])]),
+        packet
+    );
+    assert!(day13::tokenize_and_parse("[[1],[2,3,4]]") < day13::tokenize_and_parse("[[1],4]"));
+}
+
+#[test]
+fn day15_functions() {
+    let input = day15::parse(include_bytes!("../inputs/input15.txt")).unwrap();
+    assert_eq!(4665948, day15::part1(&input));
+
+    // The row is not guessed from the size of the input
+    let mut input = day15::parse(b"Sensor at x=5, y=3: closest beacon is at x=1, y=3\n").unwrap();
+    assert_eq!(2000000, input.row);
+    assert_eq!(0, day15::part1(&input));
+    input.row = 4;
+    assert_eq!(7, day15::part1(&input));
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc-api-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+const LIMITS: Limits = Limits {
+    max_reps: 3,
+    max_secs: 1,
+    warmup: 0,
+    target_error: 1.0,
+};
+
+fn options(inputs: Inputs) -> Options {
+    Options {
+        limits: LIMITS,
+        timeout: Duration::from_secs(10),
+        inputs,
+        format: Format::Json,
+        parallel: false,
+        jobs: None,
+        record: false,
+        answers: PathBuf::from("answers.toml"),
+    }
+}
+
+#[test]
+fn run_puzzles() {
+    let mut db = AnswerDb::load(Path::new("answers.toml")).unwrap();
+    let registry = registry();
+    let day01 = &registry[&(2022, 1)];
+    let day02 = &registry[&(2022, 2)];
+
+    let puzzles = vec![(day01, Parts::Both), (day02, Parts::Part1)];
+    let (results, failures) = runner::run_puzzles(puzzles, &options(Inputs::default()), &mut db);
+    assert!(failures.is_empty());
+    let checks: Vec<_> = results
+        .iter()
+        .map(|r| (r.part1_check, r.part2_check))
+        .collect();
+    assert_eq!(
+        vec![(Check::Pass, Check::Pass), (Check::Pass, Check::Skipped)],
+        checks
+    );
+    assert_eq!(0, report::exit_code(&results, &failures));
+
+    // A puzzle which fails is reported, and so are the others
+    let dir = temp_dir("run");
+    let bad = dir.join("bad.txt");
+    std::fs::write(&bad, "1\nx\n").unwrap();
+    let inputs = Inputs {
+        input: Some(bad),
+        ..Inputs::default()
+    };
+    let mut parallel = options(inputs);
+    parallel.parallel = true;
+    let (results, failures) = runner::run_puzzles(vec![(day01, Parts::Both)], &parallel, &mut db);
+    assert!(results.is_empty());
+    assert_eq!("01", failures[0].0);
+    assert!(
+        failures[0].1.starts_with("bad input: "),
+        "{}",
+        failures[0].1
+    );
+    assert_eq!(1, report::exit_code(&results, &failures));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn run_solve() {
+    let timeout = Duration::from_secs(10);
+    let example = Path::new("inputs/example01.txt");
+    assert_eq!(Ok(()), runner::run_solve("1", 2022, Some(example), timeout));
+    assert_eq!(
+        Err("no such day: 2021-01".to_string()),
+        runner::run_solve("2021:1", 2022, Some(example), timeout)
+    );
+    let err = runner::run_solve("1", 2022, Some(Path::new("no/such/file")), timeout).unwrap_err();
+    assert!(err.starts_with("Failed to read no/such/file"), "{}", err);
+    let err = runner::run_solve("2", 2022, Some(example), timeout).unwrap_err();
+    assert!(err.starts_with("Day 02: bad input: "), "{}", err);
+}
+
+#[test]
+fn run_crosscheck() {
+    let dir = temp_dir("crosscheck");
+    let config = dir.join("crosscheck.toml");
+    let inputs = Inputs {
+        input: Some(PathBuf::from("inputs/example01.txt")),
+        ..Inputs::default()
+    };
+    let selectors = ["1".to_string()];
+    let timeout = Duration::from_secs(10);
+    let check = |command: &str, generated| {
+        let toml = format!(
+            "default = {{ command = [\"sh\", \"-c\", \"{}\"] }}\n",
+            command
+        );
+        std::fs::write(&config, toml).unwrap();
+        crosscheck::run_crosscheck(&selectors, &config, generated, 2022, &inputs, timeout)
+    };
+    assert_eq!(Ok(true), check("echo 24000; echo 45000", None));
+    // Both answers on one line, so part 2 is missing
+    assert_eq!(Ok(false), check("echo 24000 45000", None));
+    assert_eq!(Ok(false), check("exit 1", None));
+    // Generated inputs have other answers
+    assert_eq!(
+        Ok(false),
+        check("echo 24000; echo 45000", Some((1..3, Some(10))))
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn run_scale() {
+    let dir = temp_dir("scale");
+    let csv = dir.join("day01.csv");
+    let timeout = Duration::from_secs(10);
+    scale::run_scale(1, &dir, &[10, 20], 1, Some(&csv), &LIMITS, timeout).unwrap();
+    let csv = std::fs::read_to_string(csv).unwrap();
+    // A header and one line per size
+    assert_eq!(3, csv.lines().count(), "{}", csv);
+    assert_eq!(2, scale::inputs(&dir, 1).unwrap().len());
+
+    std::fs::create_dir(scale::day_dir(&dir, 2)).unwrap();
+    let err = scale::run_scale(2, &dir, &[], 1, None, &LIMITS, timeout).unwrap_err();
+    assert!(err.starts_with("No inputs in"), "{}", err);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn run_visualize() {
+    let dir = temp_dir("visualize");
+    let inputs = Inputs {
+        input: Some(PathBuf::from("inputs/example14.txt")),
+        ..Inputs::default()
+    };
+    let export = Export {
+        frames_dir: Some(dir.join("frames")),
+        ..Export::default()
+    };
+    let playback = Playback {
+        fps: 10.0,
+        step: false,
+        every: 5,
+        view: (100, 40),
+    };
+    let style = Style {
+        cell: 1,
+        palette: Default::default(),
+    };
+    visualize::run_visualize("14", 2022, &inputs, &export, &playback, &style).unwrap();
+    let frames = std::fs::read_dir(dir.join("frames")).unwrap().count();
+    assert_eq!(5, frames);
+
+    let err = visualize::run_visualize("1", 2022, &inputs, &export, &playback, &style).unwrap_err();
+    assert!(err.starts_with("Day 01 cannot be visualized"), "{}", err);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn watch_run_args() {
+    let args = watch::run_args(
+        &["7".to_string(), "!9".to_string()],
+        2022,
+        Duration::from_secs(30),
+        Path::new("answers.toml"),
+    );
+    assert_eq!(
+        vec![
+            "--year=2022",
+            "--timeout=30",
+            "--answers=answers.toml",
+            "--format=json",
+            "7",
+            "!9"
+        ],
+        args
+    );
+}
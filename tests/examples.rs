@@ -0,0 +1,95 @@
+// Worked examples from the puzzle texts, read from inputs/exampleNN.txt at
+// runtime.
+
+use aoc2022rust::solution::{self, Answers, Solution};
+use aoc2022rust::*;
+use std::time::Duration;
+
+fn example<S: Solution>(day: u8) -> Answers {
+    let path = format!("inputs/example{:02}.txt", day);
+    let input = std::fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path, err));
+    solution::solve::<S>(&input).unwrap().0
+}
+
+macro_rules! example_test {
+    ($name:ident, $solution:ty, $day:expr, $part1:expr, $part2:expr) => {
+        #[test]
+        fn $name() {
+            let answer = example::<$solution>($day);
+            assert_eq!($part1, answer.part1, "part 1");
+            assert_eq!($part2, answer.part2, "part 2");
+        }
+    };
+}
+
+const DAY10_CRT: &str = "\
+██..██..██..██..██..██..██..██..██..██..\
+███...███...███...███...███...███...███.\
+████....████....████....████....████....\
+█████.....█████.....█████.....█████.....\
+██████......██████......██████......████\
+███████.......███████.......███████.....\
+";
+
+example_test!(day01, day01::Day01, 1, "24000", "45000");
+example_test!(day02, day02::Day02, 2, "15", "12");
+example_test!(day03, day03::Day03, 3, "157", "70");
+example_test!(day04, day04::Day04, 4, "2", "4");
+example_test!(day05, day05::Day05, 5, "CMZ", "MCD");
+example_test!(day06, day06::Day06, 6, "7", "19");
+example_test!(day07, day07::Day07, 7, "95437", "24933642");
+example_test!(day08, day08::Day08, 8, "21", "8");
+example_test!(day09, day09::Day09, 9, "13", "1");
+example_test!(day11, day11::Day11, 11, "10605", "2713310158");
+example_test!(day12, day12::Day12, 12, "31", "29");
+example_test!(day13, day13::Day13, 13, "13", "140");
+example_test!(day14, day14::Day14, 14, "24", "93");
+example_test!(day16, day16::Day16, 16, "1651", "1707");
+example_test!(day17, day17::Day17, 17, "3068", "1514285714288");
+example_test!(day18, day18::Day18, 18, "64", "58");
+example_test!(day19, day19::Day19, 19, "33", "3472");
+example_test!(day20, day20::Day20, 20, "3", "1623178306");
+example_test!(day21, day21::Day21, 21, "152", "301");
+example_test!(day22, day22::Day22, 22, "6032", "5031");
+example_test!(day23, day23::Day23, 23, "110", "20");
+example_test!(day24, day24::Day24, 24, "18", "54");
+example_test!(day25, day25::Day25, 25, "2=-1=0", "");
+
+// The example checks another row than the real inputs
+#[test]
+fn day15() {
+    let input = std::fs::read("inputs/example15.txt").unwrap();
+    let mut input = day15::parse(&input).unwrap();
+    assert_eq!(day15::ROW, input.row);
+    input.row = 10;
+    assert_eq!(26, day15::part1(&input));
+    assert_eq!(56000011, day15::part2(&input));
+}
+
+// The example screen does not show any letters, so it is checked as it is
+#[test]
+fn day10() {
+    let input = std::fs::read("inputs/example10.txt").unwrap();
+    let instrs = day10::Day10::parse(&input).unwrap();
+    assert_eq!(13140, day10::Day10::part1(&instrs));
+    let screen = day10::solve_p2(&instrs);
+    assert_eq!(DAY10_CRT, screen);
+    let rows: Vec<String> = screen
+        .chars()
+        .collect::<Vec<char>>()
+        .chunks(40)
+        .map(|row| row.iter().collect())
+        .collect();
+    assert_eq!(6, rows.len());
+    assert_eq!("██..██..██..██..██..██..██..██..██..██..", rows[0]);
+
+    // Reading it as letters fails part 2, instead of giving a wrong answer
+    let err = ocr::read(DAY10_CRT).unwrap_err();
+    let p = &registry::registry()[&(2022, 10)];
+    let failure = runner::solve_isolated(p, &input, solution::Parts::Both, Duration::from_secs(10))
+        .unwrap_err();
+    assert_eq!(
+        format!("panicked: cannot read the screen: {}", err),
+        failure.to_string()
+    );
+}
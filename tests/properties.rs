@@ -0,0 +1,164 @@
+// Property tests of the core algorithms, checked against brute force
+// versions which are too slow (or too simple) for the real inputs.
+
+use aoc2022rust::day13::{self, Packet};
+use aoc2022rust::{day04, day09, day15, day25};
+use proptest::prelude::*;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+fn touching(a: (i32, i32), b: (i32, i32)) -> bool {
+    (a.0 - b.0).abs() <= 1 && (a.1 - b.1).abs() <= 1
+}
+
+// Segments which are horizontal, vertical or diagonal, as in day 15
+type Segment = ((i128, i128), (i128, i128));
+
+fn segment(direction: (i128, i128)) -> impl Strategy<Value = Segment> {
+    (-30i128..30, -30i128..30, 1i128..20)
+        .prop_map(move |(x, y, len)| ((x, y), (x + direction.0 * len, y + direction.1 * len)))
+}
+
+// Two segments in different directions, which have at most one point in
+// common
+fn segments() -> impl Strategy<Value = (Segment, Segment)> {
+    let directions = vec![(1, 0), (0, 1), (1, 1), (1, -1)];
+    prop::sample::subsequence(directions, 2).prop_flat_map(|d| (segment(d[0]), segment(d[1])))
+}
+
+fn points(((ax, ay), (bx, by)): Segment) -> HashSet<(i128, i128)> {
+    let (dx, dy) = ((bx - ax).signum(), (by - ay).signum());
+    let len = (bx - ax).abs().max((by - ay).abs());
+    (0..=len).map(|i| (ax + i * dx, ay + i * dy)).collect()
+}
+
+fn range() -> impl Strategy<Value = RangeInclusive<i32>> {
+    (0i32..30, 0i32..10).prop_map(|(start, len)| start..=start + len)
+}
+
+// Packets as in the puzzle text, compared by its rules
+#[derive(Debug, Clone)]
+enum Value {
+    Int(u8),
+    List(Vec<Value>),
+}
+
+fn value() -> impl Strategy<Value = Value> {
+    let leaf = (0u8..11).prop_map(Value::Int);
+    leaf.prop_recursive(4, 32, 4, |inner| {
+        prop::collection::vec(inner, 0..4).prop_map(Value::List)
+    })
+}
+
+fn list() -> impl Strategy<Value = Value> {
+    prop::collection::vec(value(), 0..4).prop_map(Value::List)
+}
+
+fn render(v: &Value) -> String {
+    match v {
+        Value::Int(n) => n.to_string(),
+        Value::List(items) => format!(
+            "[{}]",
+            items.iter().map(render).collect::<Vec<_>>().join(",")
+        ),
+    }
+}
+
+fn packet(v: &Value) -> Packet {
+    match v {
+        Value::Int(n) => Packet::INT(*n as i64),
+        Value::List(items) => Packet::LIST(items.iter().map(packet).collect()),
+    }
+}
+
+fn order(left: &Value, right: &Value) -> Ordering {
+    match (left, right) {
+        (Value::Int(l), Value::Int(r)) => l.cmp(r),
+        (Value::List(l), Value::List(r)) => l
+            .iter()
+            .zip(r)
+            .map(|(l, r)| order(l, r))
+            .find(|o| *o != Ordering::Equal)
+            .unwrap_or(l.len().cmp(&r.len())),
+        (Value::Int(_), _) => order(&Value::List(vec![left.clone()]), right),
+        (_, Value::Int(_)) => order(left, &Value::List(vec![right.clone()])),
+    }
+}
+
+proptest! {
+    #[test]
+    fn snafu_round_trips(n in 1i64..1_000_000_000_000_000) {
+        let s = day25::rev_snafu(n);
+        prop_assert!(!s.starts_with('0'));
+        prop_assert!(s.chars().all(|c| "=-012".contains(c)));
+        prop_assert_eq!(n, day25::snafu(&s));
+    }
+
+    #[test]
+    fn snafu_strings_round_trip(s in "[12][=\\-012]{0,20}") {
+        prop_assert_eq!(&s, &day25::rev_snafu(day25::snafu(&s)));
+    }
+
+    #[test]
+    fn count_intervals(starts in prop::collection::vec((-50i128..50, 0i128..20), 0..10)) {
+        let mut intervals: Vec<day15::Interval> =
+            starts.iter().map(|(start, len)| (*start, start + len)).collect();
+        let covered: HashSet<i128> = intervals.iter().flat_map(|(a, b)| *a..=*b).collect();
+        intervals.sort();
+        prop_assert_eq!(covered.len() as i128, day15::count_intervals(&mut intervals));
+    }
+
+    #[test]
+    fn intersects((a, b) in segments(), swap in any::<bool>(), reverse in any::<bool>()) {
+        let (a, mut b) = if swap { (b, a) } else { (a, b) };
+        if reverse {
+            b = (b.1, b.0);
+        }
+        let common: Vec<_> = points(a).intersection(&points(b)).copied().collect();
+        prop_assert_eq!(common.first().copied(), day15::intersects(a, b));
+    }
+
+    #[test]
+    fn ranges(a in range(), b in range()) {
+        let (sa, sb): (HashSet<i32>, HashSet<i32>) = (a.clone().collect(), b.clone().collect());
+        prop_assert_eq!(sa.is_subset(&sb) || sb.is_subset(&sa), day04::contains_either(&a, &b));
+        prop_assert_eq!(!sa.is_disjoint(&sb), day04::overlaps(&a, &b));
+    }
+
+    #[test]
+    fn knot_follows(h in (-2i32..=2, -2i32..=2)) {
+        let t = (0, 0);
+        let moved = day09::move_follow(&h, &t);
+        prop_assert!(touching(h, moved));
+        prop_assert!(touching(t, moved));
+        if touching(h, t) {
+            prop_assert_eq!(t, moved);
+        }
+    }
+
+    #[test]
+    fn rope_stays_together(moves in prop::collection::vec((0usize..4, 1usize..6), 0..40)) {
+        let mut rope = [(0, 0); 10];
+        for (dir, steps) in moves {
+            let (dx, dy) = [(0, -1), (0, 1), (-1, 0), (1, 0)][dir];
+            for _ in 0..steps {
+                rope[0] = (rope[0].0 + dx, rope[0].1 + dy);
+                for k in 1..rope.len() {
+                    rope[k] = day09::move_follow(&rope[k - 1], &rope[k]);
+                }
+                prop_assert!(rope.windows(2).all(|w| touching(w[0], w[1])));
+            }
+        }
+    }
+
+    #[test]
+    fn packet_order(left in list(), right in list()) {
+        let l = day13::tokenize_and_parse(&render(&left));
+        let r = day13::tokenize_and_parse(&render(&right));
+        // The parser wraps the packet in a list of its own
+        prop_assert_eq!(&Packet::LIST(vec![packet(&left)]), &l);
+        prop_assert_eq!(Some(order(&left, &right)), l.partial_cmp(&r));
+        prop_assert_eq!(Some(order(&right, &left)), r.partial_cmp(&l));
+    }
+}